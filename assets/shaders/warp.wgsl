@@ -0,0 +1,31 @@
+// The original's perspective lens: the view is a window onto a wide image,
+// drawn taller towards its left and right edges.
+
+struct Warp {
+    scroll: f32;
+    view: f32;
+    strength: f32;
+};
+
+[[group(1), binding(0)]]
+var<uniform> warp: Warp;
+[[group(1), binding(1)]]
+var texture: texture_2d<f32>;
+[[group(1), binding(2)]]
+var texture_sampler: sampler;
+
+struct FragmentInput {
+    [[builtin(front_facing)]] is_front: bool;
+    [[location(0)]] world_position: vec4<f32>;
+    [[location(1)]] world_normal: vec3<f32>;
+    [[location(2)]] uv: vec2<f32>;
+};
+
+[[stage(fragment)]]
+fn fragment(in: FragmentInput) -> [[location(0)]] vec4<f32> {
+    // -1 at the left edge of the view, 1 at the right
+    let x = in.uv.x * 2.0 - 1.0;
+    let y = 0.5 + (in.uv.y - 0.5) / (1.0 + warp.strength * x * x);
+    let u = warp.scroll + in.uv.x * warp.view;
+    return textureSample(texture, texture_sampler, vec2<f32>(u, y));
+}
@@ -0,0 +1,429 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::{
+    despawn_screen,
+    doors::{Doors, Jumpscare, Side},
+    input::Action,
+    locale::Locale,
+    mods::script::Actors,
+    night::{Dawn, Power},
+    save::Config,
+    title::OnTitleScreen,
+    transition::Transition,
+    GameState,
+};
+
+/// Seconds an unlock toast stays up.
+const TOAST: f32 = 4.0;
+
+/// Achievements, unlocked by sending `Unlock` and kept in the save file.
+///
+/// The triggers below watch the night's events; features that have an
+/// achievement of their own send `Unlock` themselves. Nothing unlocks while a
+/// cheat is on. Unlocks pop up a toast
+/// over any screen and are listed on the trophy screen, opened from the
+/// title.
+pub struct AchievementPlugin;
+
+impl Plugin for AchievementPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Unlock>()
+            .init_resource::<Record>()
+            .init_resource::<Toasts>()
+            .add_startup_system(setup)
+            .add_system(dawn)
+            .add_system(unlock.after(dawn).after(scared))
+            .add_system(toast.after(unlock))
+            .add_system_set(SystemSet::on_enter(GameState::Frame1).with_system(start))
+            .add_system_set(
+                SystemSet::on_update(GameState::Frame1)
+                    .with_system(record)
+                    .with_system(scared),
+            )
+            .add_system_set(SystemSet::on_enter(GameState::Title).with_system(trophy_button))
+            .add_system_set(SystemSet::on_update(GameState::Title).with_system(open))
+            .add_system_set(SystemSet::on_enter(GameState::Trophies).with_system(gallery))
+            .add_system_set(SystemSet::on_update(GameState::Trophies).with_system(back))
+            .add_system_set(
+                SystemSet::on_exit(GameState::Trophies)
+                    .with_system(despawn_screen::<OnTrophyScreen>),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Achievement {
+    /// Survive the first night.
+    ClockedIn,
+    /// Survive a night without using the left door or its light.
+    OpenDoorPolicy,
+    /// Finish night 5 with more than 20% power.
+    PowerToSpare,
+    /// Beat night 7 with the originals at 20 all night.
+    FourTwenty,
+    /// See Golden Freddy, locked until he's in.
+    ItsMe,
+    /// Get jumpscared.
+    GotYou,
+}
+
+impl Achievement {
+    pub const ALL: [Achievement; 6] = [
+        Achievement::ClockedIn,
+        Achievement::OpenDoorPolicy,
+        Achievement::PowerToSpare,
+        Achievement::FourTwenty,
+        Achievement::ItsMe,
+        Achievement::GotYou,
+    ];
+
+    /// Stored in the save file and keying its strings, `achievement.<id>`
+    /// and `achievement.<id>.description`.
+    pub fn id(self) -> &'static str {
+        match self {
+            Achievement::ClockedIn => "clocked_in",
+            Achievement::OpenDoorPolicy => "open_door_policy",
+            Achievement::PowerToSpare => "power_to_spare",
+            Achievement::FourTwenty => "four_twenty",
+            Achievement::ItsMe => "its_me",
+            Achievement::GotYou => "got_you",
+        }
+    }
+
+    /// Hidden achievements don't tell how to get them until unlocked.
+    pub fn hidden(self) -> bool {
+        matches!(self, Achievement::GotYou)
+    }
+}
+
+pub struct Unlock(pub Achievement);
+
+/// What the player did this night, for the achievements checked at 6 AM.
+#[derive(Default)]
+struct Record {
+    /// The left door or its light was used.
+    left_door: bool,
+}
+
+#[derive(Default)]
+struct Toasts {
+    queue: VecDeque<Achievement>,
+    /// The toast up and the seconds it has left.
+    shown: Option<(Entity, f32)>,
+}
+
+#[derive(Component)]
+struct ToastRoot;
+
+#[derive(Component)]
+struct TrophyButton;
+
+#[derive(Component)]
+struct OnTrophyScreen;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(16.0),
+                    right: Val::Px(16.0),
+                    ..default()
+                },
+                flex_direction: FlexDirection::ColumnReverse,
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(ToastRoot);
+}
+
+fn start(mut record: ResMut<Record>) {
+    *record = Record::default();
+}
+
+fn record(doors: Res<Doors>, mut record: ResMut<Record>) {
+    let left = doors.get(Side::Left);
+    if doors.is_changed() && (left.closed || left.light) {
+        record.left_door = true;
+    }
+}
+
+fn dawn(
+    config: Res<Config>,
+    power: Res<Power>,
+    record: Res<Record>,
+    actors: Res<Actors>,
+    mut dawns: EventReader<Dawn>,
+    mut unlocks: EventWriter<Unlock>,
+) {
+    for _ in dawns.iter() {
+        if config.level() == 1 {
+            unlocks.send(Unlock(Achievement::ClockedIn));
+        }
+        if !record.left_door {
+            unlocks.send(Unlock(Achievement::OpenDoorPolicy));
+        }
+        if config.level() == 5 && power.0 > 20.0 {
+            unlocks.send(Unlock(Achievement::PowerToSpare));
+        }
+        let twenty = actors
+            .actors
+            .iter()
+            .filter(|a| a.original.is_some())
+            .all(|a| (0..6).all(|hour| a.ai(hour) == 20));
+        if config.level() == 7 && twenty {
+            unlocks.send(Unlock(Achievement::FourTwenty));
+        }
+    }
+}
+
+fn scared(mut jumpscares: EventReader<Jumpscare>, mut unlocks: EventWriter<Unlock>) {
+    if jumpscares.iter().next().is_some() {
+        unlocks.send(Unlock(Achievement::GotYou));
+    }
+}
+
+/// Saves new unlocks and queues their toasts.
+fn unlock(
+    mut config: ResMut<Config>,
+    mut toasts: ResMut<Toasts>,
+    mut unlocks: EventReader<Unlock>,
+) {
+    for Unlock(achievement) in unlocks.iter() {
+        if config.cheats().any() {
+            info!(
+                "achievement: {} not granted, cheats are on",
+                achievement.id()
+            );
+            continue;
+        }
+        if config.unlock(achievement.id()) {
+            info!("achievement: {}", achievement.id());
+            toasts.queue.push_back(*achievement);
+        }
+    }
+}
+
+/// Shows the queued toasts one at a time.
+fn toast(
+    mut commands: Commands,
+    time: Res<Time>,
+    locale: Res<Locale>,
+    mut toasts: ResMut<Toasts>,
+    root: Query<Entity, With<ToastRoot>>,
+) {
+    if let Some((entity, left)) = &mut toasts.shown {
+        *left -= time.delta_seconds();
+        if *left > 0.0 {
+            return;
+        }
+        commands.entity(*entity).despawn_recursive();
+        toasts.shown = None;
+    }
+
+    let achievement = match toasts.queue.pop_front() {
+        Some(achievement) => achievement,
+        None => return,
+    };
+    let root = match root.get_single() {
+        Ok(root) => root,
+        Err(_) => return,
+    };
+
+    let style = |size, color| TextStyle {
+        font: locale.font.clone(),
+        font_size: size,
+        color,
+    };
+    let mut entity = None;
+    commands.entity(root).with_children(|parent| {
+        let toast = parent
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    padding: Rect::all(Val::Px(8.0)),
+                    flex_direction: FlexDirection::ColumnReverse,
+                    ..default()
+                },
+                color: UiColor(Color::rgba(0.0, 0.0, 0.0, 0.8)),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        locale.text("achievement.unlocked"),
+                        style(16.0, Color::GRAY),
+                        default(),
+                    ),
+                    ..default()
+                });
+                parent.spawn_bundle(TextBundle {
+                    text: Text::with_section(
+                        locale.text(&format!("achievement.{}", achievement.id())),
+                        style(24.0, Color::WHITE),
+                        default(),
+                    ),
+                    ..default()
+                });
+            })
+            .id();
+        entity = Some(toast);
+    });
+    toasts.shown = entity.map(|entity| (entity, TOAST));
+}
+
+fn trophy_button(mut commands: Commands, locale: Res<Locale>) {
+    commands
+        .spawn_bundle(ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    left: Val::Px(16.0),
+                    bottom: Val::Px(16.0),
+                    ..default()
+                },
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(TrophyButton)
+        .insert(OnTitleScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    locale.text("achievement.trophies"),
+                    TextStyle {
+                        font: locale.font.clone(),
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                    },
+                    default(),
+                ),
+                ..default()
+            });
+        });
+}
+
+fn open(
+    mut transitions: EventWriter<Transition>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<TrophyButton>)>,
+) {
+    if buttons.iter().any(|i| *i == Interaction::Clicked) {
+        transitions.send(Transition::To(GameState::Trophies));
+    }
+}
+
+/// Every achievement, greyed out until unlocked.
+fn gallery(mut commands: Commands, config: Res<Config>, locale: Res<Locale>) {
+    let style = |size, color| TextStyle {
+        font: locale.font.clone(),
+        font_size: size,
+        color,
+    };
+    let unlocked = Achievement::ALL
+        .iter()
+        .filter(|a| config.unlocked(a.id()))
+        .count();
+
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(OnTrophyScreen);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: UiColor(Color::BLACK),
+            ..default()
+        })
+        .insert(OnTrophyScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    margin: Rect::all(Val::Px(16.0)),
+                    ..default()
+                },
+                text: Text::with_section(
+                    locale.fill(
+                        "achievement.count",
+                        &[
+                            ("unlocked", &unlocked.to_string()),
+                            ("total", &Achievement::ALL.len().to_string()),
+                        ],
+                    ),
+                    style(40.0, Color::WHITE),
+                    default(),
+                ),
+                ..default()
+            });
+
+            for achievement in Achievement::ALL {
+                let id = achievement.id();
+                let (color, description) = match config.unlocked(id) {
+                    true => (Color::WHITE, format!("achievement.{}.description", id)),
+                    false if achievement.hidden() => {
+                        (Color::DARK_GRAY, "achievement.hidden".to_string())
+                    }
+                    false => (Color::DARK_GRAY, format!("achievement.{}.description", id)),
+                };
+
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            margin: Rect::all(Val::Px(6.0)),
+                            flex_direction: FlexDirection::ColumnReverse,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        color: UiColor(Color::NONE),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                locale.text(&format!("achievement.{}", id)),
+                                style(24.0, color),
+                                default(),
+                            ),
+                            ..default()
+                        });
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                locale.text(&description),
+                                style(16.0, color),
+                                default(),
+                            ),
+                            ..default()
+                        });
+                    });
+            }
+
+            parent.spawn_bundle(TextBundle {
+                style: Style {
+                    margin: Rect::all(Val::Px(16.0)),
+                    ..default()
+                },
+                text: Text::with_section(
+                    locale.text("achievement.back"),
+                    style(16.0, Color::GRAY),
+                    default(),
+                ),
+                ..default()
+            });
+        });
+}
+
+fn back(actions: Res<Input<Action>>, mut transitions: EventWriter<Transition>) {
+    if actions.just_pressed(Action::Back) || actions.just_pressed(Action::Confirm) {
+        transitions.send(Transition::To(GameState::Title));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::{
+    doors::Side,
+    map::{Animatronic, Room},
+    night::RATE,
+};
+
+use Room::*;
+
+/// What keeps one of the originals from moving at an opportunity.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Watch {
+    /// Nothing, the dice alone decide.
+    Ignores,
+    /// The camera on its room, like Freddy.
+    Room,
+    /// The monitor being up at all, like Foxy.
+    Monitor,
+}
+
+/// How one of the original four moves, copied from the original's AI.
+pub struct Original {
+    pub animatronic: Animatronic,
+    pub start: Room,
+    /// Where a closed door sends it.
+    pub back: Room,
+    /// Ticks between movement opportunities.
+    pub every: u64,
+    pub watch: Watch,
+    /// The door it runs at, like Foxy, instead of waiting in the doorway.
+    pub runs: Option<Side>,
+    pub moves: &'static [(Room, &'static [Room])],
+}
+
+/// Seconds between opportunities to ticks, rounded to the nearest.
+const fn ticks(hundredths: u64) -> u64 {
+    (hundredths * RATE + 50) / 100
+}
+
+#[rustfmt::skip]
+pub const ORIGINALS: [Original; 4] = [
+    Original {
+        animatronic: Animatronic::Freddy,
+        start: ShowStage,
+        back: EastHall,
+        every: ticks(302),
+        watch: Watch::Room,
+        runs: None,
+        moves: &[
+            (ShowStage, &[DiningArea]),
+            (DiningArea, &[Restrooms]),
+            (Restrooms, &[Kitchen]),
+            (Kitchen, &[EastHall]),
+            (EastHall, &[EastHallCorner]),
+            (EastHallCorner, &[RightDoor]),
+            (RightDoor, &[Office]),
+        ],
+    },
+    Original {
+        animatronic: Animatronic::Bonnie,
+        start: ShowStage,
+        back: DiningArea,
+        every: ticks(497),
+        watch: Watch::Ignores,
+        runs: None,
+        moves: &[
+            (ShowStage, &[DiningArea, Backstage]),
+            (DiningArea, &[Backstage, WestHall]),
+            (Backstage, &[DiningArea, WestHall]),
+            (WestHall, &[SupplyCloset, WestHallCorner]),
+            (SupplyCloset, &[WestHall, LeftDoor]),
+            (WestHallCorner, &[SupplyCloset, LeftDoor]),
+            (LeftDoor, &[Office]),
+        ],
+    },
+    Original {
+        animatronic: Animatronic::Chica,
+        start: ShowStage,
+        back: DiningArea,
+        every: ticks(498),
+        watch: Watch::Ignores,
+        runs: None,
+        moves: &[
+            (ShowStage, &[DiningArea]),
+            (DiningArea, &[Restrooms, Kitchen]),
+            (Restrooms, &[Kitchen, EastHall]),
+            (Kitchen, &[Restrooms, EastHall]),
+            (EastHall, &[DiningArea, EastHallCorner]),
+            (EastHallCorner, &[EastHall, RightDoor]),
+            (RightDoor, &[Office]),
+        ],
+    },
+    Original {
+        animatronic: Animatronic::Foxy,
+        start: PirateCove,
+        back: PirateCove,
+        every: ticks(501),
+        watch: Watch::Monitor,
+        runs: Some(Side::Left),
+        moves: &[
+            (PirateCove, &[WestHall]),
+            (WestHall, &[Office]),
+        ],
+    },
+];
+
+/// Percent of power a runner drains knocking on a closed door, more every
+/// time like Foxy: 1, 6, 11...
+pub fn knock(knocks: u32) -> f32 {
+    1.0 + 5.0 * knocks as f32
+}
+
+/// AI levels at 12 AM on nights 1 to 6, in `Animatronic::ALL` order. Freddy
+/// is 1 or 2 at random on night 4 in the original, 1 here.
+const BASE: [[u8; 4]; 6] = [
+    [0, 0, 0, 0],
+    [0, 3, 1, 1],
+    [1, 0, 5, 2],
+    [1, 2, 4, 6],
+    [3, 5, 7, 5],
+    [4, 10, 12, 16],
+];
+
+/// AI levels of `animatronic` from 12 AM to 5 AM on night `level`: Bonnie
+/// gains one at 2 AM, and Bonnie, Chica and Foxy one more at 3 and 4 AM.
+/// Nights past the sixth play like it.
+pub fn levels(animatronic: Animatronic, level: u8) -> [u8; 6] {
+    let base = BASE[(level.clamp(1, 6) - 1) as usize][animatronic as usize];
+    let mut levels = [base; 6];
+    let gains = |hour| match (animatronic, hour) {
+        (Animatronic::Freddy, _) => false,
+        (Animatronic::Bonnie, _) => true,
+        (_, hour) => hour > 2,
+    };
+    for hour in 2..5 {
+        if gains(hour) {
+            levels[hour..].iter_mut().for_each(|l| *l += 1);
+        }
+    }
+    levels
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_rise_through_the_night() {
+        assert_eq!(levels(Animatronic::Freddy, 5), [3; 6]);
+        assert_eq!(levels(Animatronic::Bonnie, 2), [3, 3, 4, 5, 6, 6]);
+        assert_eq!(levels(Animatronic::Chica, 3), [5, 5, 5, 6, 7, 7]);
+        assert_eq!(levels(Animatronic::Foxy, 9), [16, 16, 16, 17, 18, 18]);
+    }
+
+    #[test]
+    fn every_original_can_get_in() {
+        for original in ORIGINALS {
+            let mut room = original.start;
+            for _ in 0..original.moves.len() {
+                if room == Office {
+                    break;
+                }
+                let (_, to) = original
+                    .moves
+                    .iter()
+                    .find(|(from, _)| *from == room)
+                    .unwrap();
+                room = *to.last().unwrap();
+            }
+            assert_eq!(room, Office, "{:?}", original.animatronic);
+        }
+    }
+}
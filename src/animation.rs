@@ -0,0 +1,183 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::{
+    save::Config,
+    tuning::{AccessibilityTuning, Tuning},
+    GameState,
+};
+
+/// Runs `Animated` sprite sheets while the given state is active.
+pub struct AnimationPlugin(pub GameState);
+
+impl Plugin for AnimationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(self.0.clone())
+                .with_system(retune)
+                .with_system(animate),
+        );
+    }
+}
+
+/// How a sprite sheet flickers, read from the `[animation.<name>]` tables of
+/// the tuning file. Any timer left at 0 is off.
+#[derive(Deserialize, Clone, Default, Debug)]
+#[serde(default)]
+pub struct Animation {
+    /// Seconds per frame.
+    pub frame: f32,
+    /// Frames to cycle through, the whole sheet when 0.
+    pub frames: usize,
+    /// Relative odds of each frame. When set, frames are picked at random
+    /// instead of in order.
+    pub weights: Vec<u32>,
+    /// Seconds between alpha changes.
+    pub alpha_every: f32,
+    /// Range the alpha is picked from.
+    pub alpha: [f32; 2],
+    /// Seconds between visibility rolls.
+    pub visible_every: f32,
+    /// Odds of being visible on each roll.
+    pub visible_chance: f32,
+}
+
+impl Animation {
+    /// Within the reduced flashing limits: a narrow alpha range, slower
+    /// changes, and always visible once the next roll comes.
+    pub fn calmed(mut self, limits: &AccessibilityTuning) -> Animation {
+        let [min, max] = self.alpha;
+        if max - min > limits.alpha {
+            let mid = (min + max) / 2.0;
+            self.alpha = [mid - limits.alpha / 2.0, mid + limits.alpha / 2.0];
+        }
+
+        if self.frame > 0.0 {
+            self.frame = self.frame.max(limits.frame);
+        }
+        if self.alpha_every > 0.0 {
+            self.alpha_every = self.alpha_every.max(limits.every);
+        }
+        if self.visible_every > 0.0 {
+            self.visible_every = self.visible_every.max(limits.every);
+            self.visible_chance = 1.0;
+        }
+        self
+    }
+}
+
+#[derive(Component)]
+pub struct Animated {
+    name: String,
+    animation: Animation,
+    frame: Timer,
+    alpha: Timer,
+    visible: Timer,
+    shown: bool,
+}
+
+impl Animated {
+    pub fn new(name: &str, tuning: &Tuning, config: &Config) -> Animated {
+        let animation = tuning.animation(name, config.accessibility());
+
+        Animated {
+            name: name.to_string(),
+            frame: Timer::from_seconds(animation.frame, true),
+            alpha: Timer::from_seconds(animation.alpha_every, true),
+            visible: Timer::from_seconds(animation.visible_every, true),
+            animation,
+            shown: true,
+        }
+    }
+
+    /// Hides the sprite until shown again, whatever its rolls say. It comes
+    /// back visible, with a fresh roll.
+    pub fn show(&mut self, shown: bool, visibility: &mut Visibility) {
+        if self.shown != shown {
+            self.shown = shown;
+            self.visible.reset();
+            visibility.is_visible = shown;
+        }
+    }
+
+    fn set(&mut self, animation: Animation) {
+        self.frame
+            .set_duration(Duration::from_secs_f32(animation.frame));
+        self.alpha
+            .set_duration(Duration::from_secs_f32(animation.alpha_every));
+        self.visible
+            .set_duration(Duration::from_secs_f32(animation.visible_every));
+        self.animation = animation;
+    }
+}
+
+/// Ticks a timer, treating a zero duration as switched off.
+fn fired(timer: &mut Timer, delta: Duration) -> bool {
+    !timer.duration().is_zero() && timer.tick(delta).just_finished()
+}
+
+fn retune(tuning: Res<Tuning>, config: Res<Config>, mut query: Query<&mut Animated>) {
+    if !(tuning.is_changed() || config.is_changed()) {
+        return;
+    }
+
+    for mut animated in query.iter_mut() {
+        let animation = tuning.animation(&animated.name, config.accessibility());
+        animated.set(animation);
+    }
+}
+
+fn animate(
+    time: Res<Time>,
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    mut query: Query<(
+        &mut Animated,
+        &mut TextureAtlasSprite,
+        &Handle<TextureAtlas>,
+        &mut Visibility,
+    )>,
+) {
+    let mut rng = rand::thread_rng();
+    for (mut animated, mut sprite, handle, mut visibility) in query.iter_mut() {
+        if !animated.shown {
+            continue;
+        }
+        let animated = &mut *animated;
+        let animation = &animated.animation;
+
+        if fired(&mut animated.frame, time.delta()) {
+            if animation.weights.is_empty() {
+                let len = texture_atlases.get(handle).map_or(1, |a| a.textures.len());
+                let frames = match animation.frames {
+                    0 => len,
+                    frames => frames.min(len),
+                };
+                sprite.index = (sprite.index + 1) % frames.max(1);
+            } else {
+                let mut roll = rng.gen_range(0..animation.weights.iter().sum::<u32>().max(1));
+                for (i, weight) in animation.weights.iter().enumerate() {
+                    if roll < *weight {
+                        sprite.index = i;
+                        break;
+                    }
+                    roll -= weight;
+                }
+            }
+        }
+
+        if fired(&mut animated.alpha, time.delta()) {
+            let [min, max] = animation.alpha;
+            sprite.color.set_a(match min < max {
+                true => rng.gen_range(min..max),
+                false => min,
+            });
+        }
+
+        if fired(&mut animated.visible, time.delta()) {
+            visibility.is_visible = rng.gen::<f32>() < animation.visible_chance;
+        }
+    }
+}
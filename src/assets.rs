@@ -1,51 +1,185 @@
+use bevy::prelude::*;
 use bevy_embasset::assets;
 
-assets!(
-    pub enum GameAssets {
-        Warning = "images/Frame17/warning.png",
-        StaticFrames = "StaticFrames.png",
-        NightNumberTitleFrames = "NightNumberTitleFrames.png",
-        FreddyFrames = "FreddyFrames.png",
-        Credit = "credit.png",
-        BlipFrames = "BlipFrames.png",
-        T12 = "images/Title/12.png",
-        T13 = "images/Title/13.png",
-        T14 = "images/Title/14.png",
-        T15 = "images/Title/15.png",
-        T16 = "images/Title/16.png",
-        T17 = "images/Title/17.png",
-        T18 = "images/Title/18.png",
-        T20 = "images/Title/20.png",
-        T22 = "images/Title/22.png",
-        T430 = "images/Title/430.png",
-        T431 = "images/Title/431.png",
-        T432 = "images/Title/432.png",
-        T433 = "images/Title/433.png",
-        T434 = "images/Title/434.png",
-        T435 = "images/Title/435.png",
-        T436 = "images/Title/436.png",
-        T437 = "images/Title/437.png",
-        T438 = "images/Title/438.png",
-        T439 = "images/Title/439.png",
-        T440 = "images/Title/440.png",
-        T441 = "images/Title/441.png",
-        T442 = "images/Title/442.png",
-        T443 = "images/Title/443.png",
-        T444 = "images/Title/444.png",
-        T445 = "images/Title/445.png",
-        T448 = "images/Title/448.png",
-        T449 = "images/Title/449.png",
-        T450 = "images/Title/450.png",
-        T452 = "images/Title/452.png",
-        T475 = "images/Title/475.png",
-        T477 = "images/Title/477.png",
-        T526 = "images/Title/526.png",
-        T572 = "images/Title/572.png",
-        T588 = "images/Title/588.png",
-        Static2 = "sounds/static2.wav",
-        DarknessMusic = "sounds/darkness music.wav",
-    },
-    pub struct GameAssetsIo {
-        root = "../assets/"
-    }
+/// Declares `GameAssets` and `GameAssetsIo`, plus `GameAssets::ALL` listing
+/// every variant's name and path for tooling that walks the asset root, and
+/// `GameAssets::file` for code that needs to keep a path around.
+macro_rules! game_assets {
+    ($($name:ident = $path:tt,)*) => {
+        assets!(
+            pub enum GameAssets {
+                $($name = $path,)*
+            },
+            pub struct GameAssetsIo {
+                root = "../assets/"
+            }
+        );
+
+        impl GameAssets {
+            pub const ALL: &'static [(&'static str, &'static str)] = &[$((stringify!($name), $path),)*];
+
+            /// Path relative to the asset root.
+            pub fn file(&self) -> &'static str {
+                match self {
+                    $(GameAssets::$name => $path,)*
+                }
+            }
+        }
+    };
+}
+
+game_assets!(
+    Warning = "images/Frame17/warning.png",
+    Office = "images/Frame1/office.png",
+    OfficeLeftLight = "images/Frame1/office-left-light.png",
+    OfficeLeftLightBonnie = "images/Frame1/office-left-light-bonnie.png",
+    OfficeRightLight = "images/Frame1/office-right-light.png",
+    OfficeRightLightChica = "images/Frame1/office-right-light-chica.png",
+    CamShowStage = "images/Cameras/1a-all.png",
+    CamShowStageNoBonnie = "images/Cameras/1a-no-bonnie.png",
+    CamShowStageNoChica = "images/Cameras/1a-no-chica.png",
+    CamShowStageFreddy = "images/Cameras/1a-freddy.png",
+    CamShowStageFreddyStare = "images/Cameras/1a-freddy-stare.png",
+    CamShowStageEmpty = "images/Cameras/1a-empty.png",
+    CamDiningArea = "images/Cameras/1b-empty.png",
+    CamDiningAreaBonnie = "images/Cameras/1b-bonnie.png",
+    CamDiningAreaChica = "images/Cameras/1b-chica.png",
+    CamDiningAreaChicaClose = "images/Cameras/1b-chica-close.png",
+    CamDiningAreaFreddy = "images/Cameras/1b-freddy.png",
+    CamPirateCove = "images/Cameras/1c.png",
+    CamWestHall = "images/Cameras/2a-empty.png",
+    CamWestHallBonnie = "images/Cameras/2a-bonnie.png",
+    CamWestHallCorner = "images/Cameras/2b-empty.png",
+    CamWestHallCornerBonnie = "images/Cameras/2b-bonnie.png",
+    CamSupplyCloset = "images/Cameras/3-empty.png",
+    CamSupplyClosetBonnie = "images/Cameras/3-bonnie.png",
+    CamEastHall = "images/Cameras/4a-empty.png",
+    CamEastHallChica = "images/Cameras/4a-chica.png",
+    CamEastHallFreddy = "images/Cameras/4a-freddy.png",
+    CamEastHallCorner = "images/Cameras/4b-empty.png",
+    CamEastHallCornerChica = "images/Cameras/4b-chica.png",
+    CamEastHallCornerFreddy = "images/Cameras/4b-freddy.png",
+    CamBackstage = "images/Cameras/5-empty.png",
+    CamBackstageBonnie = "images/Cameras/5-bonnie.png",
+    CamBackstageBonnieStare = "images/Cameras/5-bonnie-stare.png",
+    CamRestrooms = "images/Cameras/7-empty.png",
+    CamRestroomsChica = "images/Cameras/7-chica.png",
+    CamRestroomsChicaClose = "images/Cameras/7-chica-close.png",
+    CamRestroomsFreddy = "images/Cameras/7-freddy.png",
+    WarpShader = "shaders/warp.wgsl",
+    TitleLayout = "layouts/title.layout.toml",
+    Tuning = "game.tuning.toml",
+    StaticFrames = "StaticFrames.png",
+    NightNumberTitleFrames = "NightNumberTitleFrames.png",
+    FreddyFrames = "FreddyFrames.png",
+    Credit = "credit.png",
+    UiFont = "fonts/ui.ttf",
+    LocaleEn = "locales/en.locale.toml",
+    LocaleEs = "locales/es.locale.toml",
+    Night1SubtitlesEn = "locales/en/calls/night1.subtitles.toml",
+    Night1SubtitlesEs = "locales/es/calls/night1.subtitles.toml",
+    BlipFrames = "BlipFrames.png",
+    T12 = "images/Title/12.png",
+    T13 = "images/Title/13.png",
+    T14 = "images/Title/14.png",
+    T15 = "images/Title/15.png",
+    T16 = "images/Title/16.png",
+    T17 = "images/Title/17.png",
+    T18 = "images/Title/18.png",
+    T20 = "images/Title/20.png",
+    T22 = "images/Title/22.png",
+    T430 = "images/Title/430.png",
+    T431 = "images/Title/431.png",
+    T432 = "images/Title/432.png",
+    T433 = "images/Title/433.png",
+    T434 = "images/Title/434.png",
+    T435 = "images/Title/435.png",
+    T436 = "images/Title/436.png",
+    T437 = "images/Title/437.png",
+    T438 = "images/Title/438.png",
+    T439 = "images/Title/439.png",
+    T440 = "images/Title/440.png",
+    T441 = "images/Title/441.png",
+    T442 = "images/Title/442.png",
+    T443 = "images/Title/443.png",
+    T444 = "images/Title/444.png",
+    T445 = "images/Title/445.png",
+    T448 = "images/Title/448.png",
+    T449 = "images/Title/449.png",
+    T450 = "images/Title/450.png",
+    T452 = "images/Title/452.png",
+    T475 = "images/Title/475.png",
+    T477 = "images/Title/477.png",
+    T526 = "images/Title/526.png",
+    T572 = "images/Title/572.png",
+    T588 = "images/Title/588.png",
+    Static2 = "sounds/static2.wav",
+    DarknessMusic = "sounds/darkness music.wav",
+    WindowScare = "sounds/windowscare.wav",
+    Scream = "sounds/XSCREAM.wav",
+    Knock = "sounds/knock2.wav",
+    Run = "sounds/run.wav",
+    Call1 = "sounds/voiceover1c.wav",
+    Call2 = "sounds/voiceover2a.wav",
+    Call3 = "sounds/voiceover3.wav",
+    Call4 = "sounds/voiceover4.wav",
+    Call5 = "sounds/voiceover5.wav",
 );
+
+/// Grid of a pre-assembled sprite sheet.
+pub struct Sheet {
+    pub tile: [u32; 2],
+    pub columns: usize,
+    pub rows: usize,
+}
+
+impl Sheet {
+    pub fn atlas(&self, texture: Handle<Image>) -> TextureAtlas {
+        TextureAtlas::from_grid(
+            texture,
+            Vec2::new(self.tile[0] as f32, self.tile[1] as f32),
+            self.columns,
+            self.rows,
+        )
+    }
+
+    /// Pixel size the whole sheet is expected to have.
+    pub fn size(&self) -> [u32; 2] {
+        [
+            self.tile[0] * self.columns as u32,
+            self.tile[1] * self.rows as u32,
+        ]
+    }
+}
+
+pub const STATIC_FRAMES: Sheet = Sheet {
+    tile: [1280, 720],
+    columns: 3,
+    rows: 3,
+};
+
+pub const BLIP_FRAMES: Sheet = Sheet {
+    tile: [1280, 720],
+    columns: 3,
+    rows: 3,
+};
+
+pub const FREDDY_FRAMES: Sheet = Sheet {
+    tile: [1280, 720],
+    columns: 2,
+    rows: 2,
+};
+
+pub const NIGHT_NUMBER_TITLE_FRAMES: Sheet = Sheet {
+    tile: [14, 17],
+    columns: 14,
+    rows: 1,
+};
+
+/// Sheets by `GameAssets` variant name.
+pub const SHEETS: &[(&str, Sheet)] = &[
+    ("StaticFrames", STATIC_FRAMES),
+    ("BlipFrames", BLIP_FRAMES),
+    ("FreddyFrames", FREDDY_FRAMES),
+    ("NightNumberTitleFrames", NIGHT_NUMBER_TITLE_FRAMES),
+];
@@ -0,0 +1,450 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use bevy::prelude::*;
+use bevy_kira_audio::{AudioApp, AudioChannel, AudioSource, InstanceHandle, PlaybackState};
+
+use crate::{assets::GameAssets, map::Room, GameState};
+
+pub mod spatial;
+
+/// Named channels, per state soundscapes with fades, and ducking.
+pub struct MixerPlugin;
+
+impl Plugin for MixerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(spatial::SpatialPlugin)
+            .add_audio_channel::<Music>()
+            .add_audio_channel::<Ambience>()
+            .add_audio_channel::<Sfx>()
+            .add_audio_channel::<Voice>()
+            .add_audio_channel::<Jumpscare>()
+            .add_layers::<Music>()
+            .add_layers::<Ambience>()
+            .add_layers::<Sfx>()
+            .add_layers::<Voice>()
+            .add_layers::<Jumpscare>()
+            .add_event::<PlaySound>()
+            .add_event::<Played>()
+            .init_resource::<Soundscapes>()
+            .insert_resource(Mixer::default())
+            .add_system(soundscape.before(Mix))
+            .add_system(mix::<Music>.label(Mix))
+            .add_system(mix::<Ambience>.label(Mix))
+            .add_system(mix::<Sfx>.label(Mix))
+            .add_system(mix::<Voice>.label(Mix))
+            .add_system(mix::<Jumpscare>.label(Mix));
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Channel {
+    Music,
+    Ambience,
+    Sfx,
+    Voice,
+    Jumpscare,
+}
+
+impl Channel {
+    pub const ALL: [Channel; 5] = [
+        Channel::Music,
+        Channel::Ambience,
+        Channel::Sfx,
+        Channel::Voice,
+        Channel::Jumpscare,
+    ];
+}
+
+pub struct Music;
+
+pub struct Ambience;
+
+pub struct Sfx;
+
+pub struct Voice;
+
+pub struct Jumpscare;
+
+pub trait Track: Send + Sync + 'static {
+    const CHANNEL: Channel;
+}
+
+impl Track for Music {
+    const CHANNEL: Channel = Channel::Music;
+}
+
+impl Track for Ambience {
+    const CHANNEL: Channel = Channel::Ambience;
+}
+
+impl Track for Sfx {
+    const CHANNEL: Channel = Channel::Sfx;
+}
+
+impl Track for Voice {
+    const CHANNEL: Channel = Channel::Voice;
+}
+
+impl Track for Jumpscare {
+    const CHANNEL: Channel = Channel::Jumpscare;
+}
+
+/// One of a track's two soundscape loops. Volume in kira is per channel, so
+/// the loop fading out and the one fading in need one each.
+struct Layer<T, const N: usize>(PhantomData<T>);
+
+type Layers<'w, T> = (
+    Res<'w, AudioChannel<Layer<T, 0>>>,
+    Res<'w, AudioChannel<Layer<T, 1>>>,
+);
+
+trait LayerApp {
+    fn add_layers<T: Track>(&mut self) -> &mut Self;
+}
+
+impl LayerApp for App {
+    fn add_layers<T: Track>(&mut self) -> &mut Self {
+        self.add_audio_channel::<Layer<T, 0>>()
+            .add_audio_channel::<Layer<T, 1>>()
+    }
+}
+
+/// Play a sound on a channel right away, outside of any soundscape.
+pub struct PlaySound {
+    pub channel: Channel,
+    pub sound: Handle<AudioSource>,
+    pub looped: bool,
+}
+
+/// Sent when a sound actually starts, for the captions.
+pub struct Played {
+    pub sound: Handle<AudioSource>,
+    /// The room it comes from, for sounds played with `spatial::PlayAt`.
+    pub room: Option<Room>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Cue {
+    pub channel: Channel,
+    pub sound: &'static str,
+    pub looped: bool,
+}
+
+impl Cue {
+    /// Plays once when the state is entered.
+    pub fn once(channel: Channel, sound: GameAssets) -> Cue {
+        Cue {
+            channel,
+            sound: sound.file(),
+            looped: false,
+        }
+    }
+
+    /// Loops for as long as the state is active, fading in and out.
+    pub fn looped(channel: Channel, sound: GameAssets) -> Cue {
+        Cue {
+            channel,
+            sound: sound.file(),
+            looped: true,
+        }
+    }
+}
+
+/// What each state plays. States without one are silent.
+#[derive(Default)]
+pub struct Soundscapes(HashMap<GameState, Vec<Cue>>);
+
+pub trait SoundscapeApp {
+    fn add_soundscape(&mut self, state: GameState, cues: &[Cue]) -> &mut Self;
+}
+
+impl SoundscapeApp for App {
+    fn add_soundscape(&mut self, state: GameState, cues: &[Cue]) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Soundscapes::default)
+            .0
+            .insert(state, cues.to_vec());
+        self
+    }
+}
+
+/// While `when` is playing, `lower` is scaled down to `to`.
+pub struct Duck {
+    pub when: Channel,
+    pub lower: Channel,
+    pub to: f32,
+}
+
+#[derive(Default)]
+struct Loop {
+    /// Fade level, 0 to 1.
+    volume: f32,
+    /// Direction of the current fade, 0 when settled.
+    fade: f32,
+    /// Looped soundscape cue on this layer.
+    scene: Option<&'static str>,
+    /// The cue was just given to this layer and hasn't started yet.
+    start: bool,
+    sent: Option<f32>,
+}
+
+#[derive(Default)]
+struct TrackState {
+    /// The soundscape loops, one fading out while the other fades in.
+    loops: [Loop; 2],
+    /// The last sound played with `PlaySound`.
+    instance: Option<InstanceHandle>,
+    /// The state the one shots were played in is over.
+    stop: bool,
+    active: bool,
+    sent: Option<f32>,
+    paused: bool,
+    /// Volume multiplier set from outside, see `Mixer::set_gain`.
+    gain: f32,
+}
+
+pub struct Mixer {
+    /// Seconds a soundscape takes to fade in or out.
+    pub fade: f32,
+    pub ducks: Vec<Duck>,
+    /// Holds every channel where it is, for the pause menu.
+    pub paused: bool,
+    tracks: HashMap<Channel, TrackState>,
+}
+
+impl Default for Mixer {
+    fn default() -> Self {
+        let tracks = Channel::ALL
+            .iter()
+            .map(|c| {
+                (
+                    *c,
+                    TrackState {
+                        gain: 1.0,
+                        ..default()
+                    },
+                )
+            })
+            .collect();
+
+        Self {
+            fade: 0.5,
+            ducks: vec![
+                // the phone call
+                Duck {
+                    when: Channel::Voice,
+                    lower: Channel::Ambience,
+                    to: 0.4,
+                },
+                Duck {
+                    when: Channel::Voice,
+                    lower: Channel::Music,
+                    to: 0.6,
+                },
+                Duck {
+                    when: Channel::Jumpscare,
+                    lower: Channel::Ambience,
+                    to: 0.0,
+                },
+                Duck {
+                    when: Channel::Jumpscare,
+                    lower: Channel::Music,
+                    to: 0.0,
+                },
+            ],
+            paused: false,
+            tracks,
+        }
+    }
+}
+
+impl Mixer {
+    /// Scales a channel's volume until changed again.
+    pub fn set_gain(&mut self, channel: Channel, gain: f32) {
+        self.tracks.get_mut(&channel).unwrap().gain = gain;
+    }
+
+    fn duck(&self, channel: Channel) -> f32 {
+        self.ducks
+            .iter()
+            .filter(|d| d.lower == channel && self.tracks[&d.when].active)
+            .map(|d| d.to)
+            .product()
+    }
+}
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct Mix;
+
+fn soundscape(
+    state: Res<State<GameState>>,
+    mut last: Local<Option<GameState>>,
+    soundscapes: Res<Soundscapes>,
+    asr: Res<AssetServer>,
+    mut mixer: ResMut<Mixer>,
+    mut sounds: EventWriter<PlaySound>,
+) {
+    // the pause menu sits on top of the night, which keeps its soundscape
+    if last.as_ref() == Some(state.current()) || *state.current() == GameState::Pause {
+        return;
+    }
+    *last = Some(state.current().clone());
+
+    let cues = soundscapes
+        .0
+        .get(state.current())
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+
+    for channel in Channel::ALL {
+        let scene = cues.iter().find(|c| c.looped && c.channel == channel);
+        let track = mixer.tracks.get_mut(&channel).unwrap();
+        track.stop = true;
+
+        for layer in &mut track.loops {
+            layer.fade = match (layer.scene, scene) {
+                (Some(current), Some(cue)) if current == cue.sound => 1.0,
+                (Some(_), _) => -1.0,
+                (None, _) => 0.0,
+            };
+        }
+
+        let cue = match scene {
+            Some(cue) if !track.loops.iter().any(|l| l.scene == Some(cue.sound)) => cue,
+            _ => continue,
+        };
+        // the quieter layer, which is free or already on its way out
+        let layer = track
+            .loops
+            .iter_mut()
+            .min_by(|a, b| a.volume.partial_cmp(&b.volume).unwrap())
+            .unwrap();
+        *layer = Loop {
+            volume: 0.0,
+            fade: 1.0,
+            scene: Some(cue.sound),
+            start: true,
+            sent: None,
+        };
+    }
+
+    for cue in cues.iter().filter(|c| !c.looped) {
+        sounds.send(PlaySound {
+            channel: cue.channel,
+            sound: asr.load(cue.sound),
+            looped: false,
+        });
+    }
+}
+
+fn mix<T: Track>(
+    time: Res<Time>,
+    asr: Res<AssetServer>,
+    mut mixer: ResMut<Mixer>,
+    mut sounds: EventReader<PlaySound>,
+    mut played: EventWriter<Played>,
+    channel: Res<AudioChannel<T>>,
+    layers: Layers<T>,
+) {
+    let step = time.delta_seconds() / mixer.fade;
+    let duck = mixer.duck(T::CHANNEL);
+    let paused = mixer.paused;
+    let track = mixer.tracks.get_mut(&T::CHANNEL).unwrap();
+
+    if track.paused != paused {
+        match paused {
+            true => {
+                channel.pause();
+                layers.0.pause();
+                layers.1.pause();
+            }
+            false => {
+                channel.resume();
+                layers.0.resume();
+                layers.1.resume();
+            }
+        }
+        track.paused = paused;
+    }
+
+    if track.stop {
+        channel.stop();
+        track.instance = None;
+        track.stop = false;
+    }
+
+    // read while paused too, the pause menu's sounds and the ones sent on
+    // the frame it opened would be lost otherwise
+    for sound in sounds.iter().filter(|s| s.channel == T::CHANNEL) {
+        track.instance = Some(match sound.looped {
+            true => channel.play_looped(sound.sound.clone()),
+            false => channel.play(sound.sound.clone()),
+        });
+        played.send(Played {
+            sound: sound.sound.clone(),
+            room: None,
+        });
+    }
+
+    if paused {
+        return;
+    }
+
+    let [first, second] = &mut track.loops;
+    let level = duck * track.gain;
+    fade(first, &layers.0, step, level, &asr, &mut played);
+    fade(second, &layers.1, step, level, &asr, &mut played);
+
+    track.active = track.loops.iter().any(|l| l.scene.is_some())
+        || match &track.instance {
+            Some(instance) => matches!(
+                channel.state(instance.clone()),
+                PlaybackState::Playing { .. } | PlaybackState::Queued
+            ),
+            None => false,
+        };
+
+    if track.sent != Some(level) {
+        channel.set_volume(level);
+        track.sent = Some(level);
+    }
+}
+
+/// Starts, fades and stops one soundscape loop.
+fn fade<L: Send + Sync + 'static>(
+    layer: &mut Loop,
+    channel: &AudioChannel<L>,
+    step: f32,
+    level: f32,
+    asr: &AssetServer,
+    played: &mut EventWriter<Played>,
+) {
+    if layer.fade != 0.0 {
+        layer.volume = (layer.volume + layer.fade * step).clamp(0.0, 1.0);
+    }
+
+    if layer.fade < 0.0 && layer.volume <= 0.0 {
+        channel.stop();
+        layer.scene = None;
+        layer.fade = 0.0;
+    } else if layer.fade > 0.0 && layer.volume >= 1.0 {
+        layer.fade = 0.0;
+    }
+
+    let volume = layer.volume * level;
+    if layer.sent != Some(volume) {
+        channel.set_volume(volume);
+        layer.sent = Some(volume);
+    }
+
+    // after the volume, so it doesn't start at the last one's
+    if layer.start {
+        if let Some(scene) = layer.scene {
+            let sound = asr.load(scene);
+            channel.stop();
+            channel.play_looped(sound.clone());
+            played.send(Played { sound, room: None });
+        }
+        layer.start = false;
+    }
+}
@@ -0,0 +1,187 @@
+use bevy::prelude::*;
+use bevy_kira_audio::{AudioApp, AudioChannel, AudioSource, InstanceHandle, PlaybackState};
+
+use super::{Mixer, Played};
+use crate::map::{Monitor, Room};
+
+/// Plays sounds from rooms on the map, panned and attenuated relative to the
+/// office. Panning in kira is per channel, so each sound gets one of a small
+/// pool of voices.
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_audio_channel::<VoiceA>()
+            .add_audio_channel::<VoiceB>()
+            .add_audio_channel::<VoiceC>()
+            .add_audio_channel::<VoiceD>()
+            .add_event::<PlayAt>()
+            .init_resource::<Monitor>()
+            .init_resource::<Voices>()
+            .add_system(assign.before(Place))
+            .add_system(place::<VoiceA>.label(Place))
+            .add_system(place::<VoiceB>.label(Place))
+            .add_system(place::<VoiceC>.label(Place))
+            .add_system(place::<VoiceD>.label(Place));
+    }
+}
+
+/// Play a sound as if it came from a room: footsteps in the halls, Chica in
+/// the kitchen, Foxy at the left door.
+pub struct PlayAt {
+    pub room: Room,
+    pub sound: Handle<AudioSource>,
+}
+
+pub struct Acoustics {
+    /// 0 is hard left, 1 hard right.
+    pub pan: f32,
+    pub volume: f32,
+    /// Volume multiplier unless the room's camera is on the monitor.
+    pub muffled: f32,
+}
+
+pub fn acoustics(room: Room) -> Acoustics {
+    let (pan, volume, muffled) = match room {
+        Room::ShowStage => (0.5, 0.25, 1.0),
+        Room::DiningArea => (0.5, 0.35, 1.0),
+        Room::PirateCove => (0.3, 0.35, 1.0),
+        Room::Backstage => (0.35, 0.3, 1.0),
+        Room::SupplyCloset => (0.25, 0.5, 1.0),
+        Room::WestHall => (0.2, 0.6, 1.0),
+        Room::WestHallCorner => (0.1, 0.75, 1.0),
+        Room::LeftDoor => (0.0, 1.0, 1.0),
+        Room::EastHall => (0.8, 0.6, 1.0),
+        Room::EastHallCorner => (0.9, 0.75, 1.0),
+        Room::RightDoor => (1.0, 1.0, 1.0),
+        Room::Restrooms => (0.75, 0.35, 1.0),
+        // CAM 6 is audio only, so the kitchen is only clear while on it
+        Room::Kitchen => (0.75, 0.4, 0.35),
+        Room::Office => (0.5, 1.0, 1.0),
+    };
+
+    Acoustics {
+        pan,
+        volume,
+        muffled,
+    }
+}
+
+pub struct VoiceA;
+
+pub struct VoiceB;
+
+pub struct VoiceC;
+
+pub struct VoiceD;
+
+pub trait Voice: Send + Sync + 'static {
+    const SLOT: usize;
+}
+
+impl Voice for VoiceA {
+    const SLOT: usize = 0;
+}
+
+impl Voice for VoiceB {
+    const SLOT: usize = 1;
+}
+
+impl Voice for VoiceC {
+    const SLOT: usize = 2;
+}
+
+impl Voice for VoiceD {
+    const SLOT: usize = 3;
+}
+
+#[derive(Default)]
+struct Slot {
+    room: Option<Room>,
+    pending: Option<Handle<AudioSource>>,
+    instance: Option<InstanceHandle>,
+    active: bool,
+    /// Frame the slot was last given a sound, to steal the oldest.
+    started: u64,
+    sent: Option<(f32, f32)>,
+    paused: bool,
+}
+
+#[derive(Default)]
+struct Voices {
+    slots: [Slot; 4],
+    frame: u64,
+}
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct Place;
+
+fn assign(mut voices: ResMut<Voices>, mut events: EventReader<PlayAt>) {
+    voices.frame += 1;
+    let frame = voices.frame;
+
+    for event in events.iter() {
+        let slot = voices
+            .slots
+            .iter_mut()
+            .min_by_key(|s| (s.active || s.pending.is_some(), s.started))
+            .unwrap();
+
+        slot.room = Some(event.room);
+        slot.pending = Some(event.sound.clone());
+        slot.started = frame;
+    }
+}
+
+fn place<T: Voice>(
+    monitor: Res<Monitor>,
+    mixer: Res<Mixer>,
+    mut voices: ResMut<Voices>,
+    mut played: EventWriter<Played>,
+    channel: Res<AudioChannel<T>>,
+) {
+    let slot = &mut voices.slots[T::SLOT];
+    if slot.paused != mixer.paused {
+        match mixer.paused {
+            true => channel.pause(),
+            false => channel.resume(),
+        }
+        slot.paused = mixer.paused;
+    }
+    if mixer.paused {
+        return;
+    }
+
+    let room = match slot.room {
+        Some(room) => room,
+        None => return,
+    };
+
+    let acoustics = acoustics(room);
+    let volume = match monitor.watching(room) {
+        true => acoustics.volume,
+        false => acoustics.volume * acoustics.muffled,
+    };
+    if slot.sent != Some((acoustics.pan, volume)) {
+        channel.set_panning(acoustics.pan);
+        channel.set_volume(volume);
+        slot.sent = Some((acoustics.pan, volume));
+    }
+
+    if let Some(sound) = slot.pending.take() {
+        channel.stop();
+        slot.instance = Some(channel.play(sound.clone()));
+        played.send(Played {
+            sound,
+            room: Some(room),
+        });
+    }
+
+    slot.active = match &slot.instance {
+        Some(instance) => matches!(
+            channel.state(instance.clone()),
+            PlaybackState::Playing { .. } | PlaybackState::Queued
+        ),
+        None => false,
+    };
+}
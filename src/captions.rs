@@ -0,0 +1,265 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    audio::{spatial, Mixer, Played},
+    locale::Locale,
+    mods::script::Actors,
+    save::Config,
+    tuning::Tuning,
+};
+
+/// Subtitles for the phone calls and captions for sounds that carry
+/// information, drawn over every screen.
+///
+/// What gets captioned is up to the language: its `[captions]` and
+/// `[subtitles]` tables, keyed by sound path. Sounds played from a room that
+/// it has no caption for, like a mod's, still get a generic one.
+pub struct CaptionPlugin;
+
+impl Plugin for CaptionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Subtitles>()
+            .init_asset_loader::<SubtitlesLoader>()
+            .init_resource::<Shown>()
+            .add_startup_system(setup)
+            .add_system(listen)
+            .add_system(advance.after(listen))
+            .add_system(render.after(advance));
+    }
+}
+
+/// A `.subtitles.toml` file, the lines of one recording in order.
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "7c9e2a5d-3f61-4b8e-a0d4-5e1b9c7f2a36"]
+pub struct Subtitles {
+    #[serde(rename = "line")]
+    pub lines: Vec<Line>,
+}
+
+#[derive(Deserialize)]
+pub struct Line {
+    /// Seconds into the recording.
+    pub start: f32,
+    pub end: f32,
+    pub text: String,
+}
+
+#[derive(Default)]
+pub struct SubtitlesLoader;
+
+impl AssetLoader for SubtitlesLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let subtitles: Subtitles = toml::from_slice(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(subtitles));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["subtitles.toml"]
+    }
+}
+
+/// A recording with subtitles that is playing.
+struct Call {
+    subtitles: Handle<Subtitles>,
+    elapsed: f32,
+}
+
+/// A sound caption and the seconds it has left.
+struct Cue {
+    text: String,
+    left: f32,
+}
+
+#[derive(Default)]
+struct Shown {
+    calls: Vec<Call>,
+    cues: Vec<Cue>,
+    /// What is drawn, to only redraw when it changes.
+    lines: Vec<String>,
+}
+
+#[derive(Component)]
+struct Overlay;
+
+fn setup(mut commands: Commands) {
+    // nights only have a 2D camera, captions have to show there too
+    commands.spawn_bundle(UiCameraBundle::default());
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Percent(8.0),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(100.0), Val::Auto),
+                flex_direction: FlexDirection::ColumnReverse,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(Overlay);
+}
+
+/// Starts the subtitles or caption for every sound that starts playing.
+fn listen(
+    asr: Res<AssetServer>,
+    config: Res<Config>,
+    locale: Res<Locale>,
+    tuning: Res<Tuning>,
+    actors: Res<Actors>,
+    mut played: EventReader<Played>,
+    mut shown: ResMut<Shown>,
+) {
+    for event in played.iter() {
+        if !config.captions().enabled {
+            continue;
+        }
+        let path = match asr.get_handle_path(&event.sound) {
+            Some(path) => path.path().to_string_lossy().replace('\\', "/"),
+            None => continue,
+        };
+
+        if let Some(subtitles) = locale.strings.subtitles.get(&path) {
+            shown.calls.push(Call {
+                subtitles: asr.load(subtitles.as_str()),
+                elapsed: 0.0,
+            });
+            continue;
+        }
+
+        let caption = match (locale.strings.captions.get(&path), event.room) {
+            (Some(caption), _) => caption.clone(),
+            (None, Some(_)) if actors.actors.iter().any(|a| a.step.as_ref() == Some(&path)) => {
+                locale.text("caption.step").to_string()
+            }
+            (None, Some(_)) => locale.text("caption.sound").to_string(),
+            (None, None) => continue,
+        };
+        let pan = event.room.map_or(0.5, |room| spatial::acoustics(room).pan);
+        let text = match pan {
+            p if p < 0.35 => locale.fill("caption.left", &[("caption", &caption)]),
+            p if p > 0.65 => locale.fill("caption.right", &[("caption", &caption)]),
+            _ => caption,
+        };
+        shown.cues.retain(|cue| cue.text != text);
+        shown.cues.push(Cue {
+            text,
+            left: tuning.captions.cue,
+        });
+    }
+}
+
+/// Runs the clocks of the shown lines, holding them while paused.
+fn advance(
+    time: Res<Time>,
+    mixer: Res<Mixer>,
+    config: Res<Config>,
+    subtitles: Res<Assets<Subtitles>>,
+    mut shown: ResMut<Shown>,
+) {
+    if !config.captions().enabled {
+        shown.calls.clear();
+        shown.cues.clear();
+    }
+    if mixer.paused {
+        return;
+    }
+
+    let delta = time.delta_seconds();
+    for call in shown.calls.iter_mut() {
+        call.elapsed += delta;
+    }
+    for cue in shown.cues.iter_mut() {
+        cue.left -= delta;
+    }
+
+    shown.cues.retain(|cue| cue.left > 0.0);
+    shown
+        .calls
+        .retain(|call| match subtitles.get(&call.subtitles) {
+            Some(s) => s.lines.iter().any(|line| line.end > call.elapsed),
+            // still loading
+            None => true,
+        });
+}
+
+fn render(
+    mut commands: Commands,
+    config: Res<Config>,
+    locale: Res<Locale>,
+    subtitles: Res<Assets<Subtitles>>,
+    mut shown: ResMut<Shown>,
+    overlay: Query<(Entity, Option<&Children>), With<Overlay>>,
+) {
+    let mut lines: Vec<String> = Vec::new();
+    for call in shown.calls.iter() {
+        if let Some(s) = subtitles.get(&call.subtitles) {
+            lines.extend(
+                s.lines
+                    .iter()
+                    .filter(|line| line.start <= call.elapsed && call.elapsed < line.end)
+                    .map(|line| line.text.clone()),
+            );
+        }
+    }
+    lines.extend(shown.cues.iter().map(|cue| cue.text.clone()));
+
+    if lines == shown.lines && !config.is_changed() && !locale.is_changed() {
+        return;
+    }
+    shown.lines = lines;
+
+    let settings = config.captions();
+    for (root, children) in overlay.iter() {
+        if let Some(children) = children {
+            for &child in children.iter() {
+                commands.entity(child).despawn_recursive();
+            }
+        }
+
+        commands.entity(root).with_children(|parent| {
+            for line in shown.lines.iter() {
+                parent
+                    .spawn_bundle(NodeBundle {
+                        style: Style {
+                            margin: Rect::all(Val::Px(settings.size / 8.0)),
+                            padding: Rect::all(Val::Px(settings.size / 4.0)),
+                            ..default()
+                        },
+                        color: UiColor(Color::rgba(0.0, 0.0, 0.0, settings.background)),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn_bundle(TextBundle {
+                            text: Text::with_section(
+                                line.as_str(),
+                                TextStyle {
+                                    font: locale.font.clone(),
+                                    font_size: settings.size,
+                                    color: Color::WHITE,
+                                },
+                                default(),
+                            ),
+                            ..default()
+                        });
+                    });
+            }
+        });
+    }
+}
@@ -0,0 +1,141 @@
+use std::path::{Path, PathBuf};
+
+use image::GenericImageView;
+
+use crate::{
+    assets::{GameAssets, SHEETS},
+    captions::Subtitles,
+    layout::{Element, Kind, Layout},
+    locale::Strings,
+    tuning::Tuning,
+};
+
+/// `frnaf check-assets [root]`
+///
+/// Walks every `GameAssets` entry under the asset root, decodes it and checks
+/// sprite sheets against the grids the game slices them with.
+pub fn run(root: Option<PathBuf>) -> bool {
+    let root = root.unwrap_or_else(default_root);
+    println!("checking assets in {}", root.display());
+
+    let mut problems = 0;
+    for (name, path) in GameAssets::ALL {
+        match check(&root, name, path) {
+            Ok(()) => println!("  ok       {}", path),
+            Err(e) => {
+                problems += 1;
+                println!("  PROBLEM  {} (GameAssets::{}): {}", path, name, e);
+            }
+        }
+    }
+
+    if problems == 0 {
+        println!("all {} assets look good", GameAssets::ALL.len());
+        true
+    } else {
+        println!(
+            "{} of {} assets have problems, see the README for how to rip them",
+            problems,
+            GameAssets::ALL.len()
+        );
+        false
+    }
+}
+
+pub fn default_root() -> PathBuf {
+    match std::env::var("CARGO_MANIFEST_DIR") {
+        Ok(dir) => PathBuf::from(dir).join("assets"),
+        Err(_) => PathBuf::from("assets"),
+    }
+}
+
+fn check(root: &Path, name: &str, path: &str) -> Result<(), String> {
+    let full = root.join(path);
+    if !full.is_file() {
+        return Err("file is missing".to_string());
+    }
+
+    if path.ends_with(".png") {
+        let size = image_size(&full)?;
+        if let Some((_, sheet)) = SHEETS.iter().find(|(n, _)| *n == name) {
+            let expected = sheet.size();
+            if size != expected {
+                return Err(format!(
+                    "sheet is {}x{}, expected {}x{} ({}x{} frames of {}x{})",
+                    size[0],
+                    size[1],
+                    expected[0],
+                    expected[1],
+                    sheet.columns,
+                    sheet.rows,
+                    sheet.tile[0],
+                    sheet.tile[1]
+                ));
+            }
+        }
+    } else if path.ends_with(".wav") {
+        let bytes = read(&full)?;
+        if bytes.len() < 12 || &bytes[0..4] != b"RIFF" || &bytes[8..12] != b"WAVE" {
+            return Err("not a RIFF/WAVE file".to_string());
+        }
+    } else if path.ends_with(".layout.toml") {
+        let layout: Layout = toml::from_slice(&read(&full)?).map_err(|e| e.to_string())?;
+        for element in layout.elements.iter() {
+            check_element(root, element)?;
+        }
+    } else if path.ends_with(".tuning.toml") {
+        toml::from_slice::<Tuning>(&read(&full)?).map_err(|e| e.to_string())?;
+    } else if path.ends_with(".locale.toml") {
+        let strings: Strings = toml::from_slice(&read(&full)?).map_err(|e| e.to_string())?;
+        for replacement in strings.images.values() {
+            if !root.join(replacement).is_file() {
+                return Err(format!(
+                    "replaces an image with {}, which is missing",
+                    replacement
+                ));
+            }
+        }
+        for file in strings.subtitles.values() {
+            let bytes = read(&root.join(file)).map_err(|e| format!("subtitles {}: {}", file, e))?;
+            toml::from_slice::<Subtitles>(&bytes).map_err(|e| format!("{}: {}", file, e))?;
+        }
+    }
+
+    Ok(())
+}
+
+fn check_element(root: &Path, element: &Element) -> Result<(), String> {
+    let full = root.join(&element.image);
+    if !full.is_file() {
+        return Err(format!("uses {}, which is missing", element.image));
+    }
+
+    if let (Kind::Atlas, Some(grid)) = (element.kind, &element.grid) {
+        let size = image_size(&full)?;
+        let expected = [
+            (grid.tile[0] * grid.columns as f32) as u32,
+            (grid.tile[1] * grid.rows as f32) as u32,
+        ];
+        if size != expected {
+            return Err(format!(
+                "atlas {} is {}x{}, but its grid needs {}x{}",
+                element.image, size[0], size[1], expected[0], expected[1]
+            ));
+        }
+    }
+
+    for child in element.children.iter() {
+        check_element(root, child)?;
+    }
+
+    Ok(())
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    std::fs::read(path).map_err(|e| e.to_string())
+}
+
+fn image_size(path: &Path) -> Result<[u32; 2], String> {
+    let image = image::open(path).map_err(|e| format!("can't decode: {}", e))?;
+    Ok([image.width(), image.height()])
+}
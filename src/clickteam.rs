@@ -0,0 +1,532 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{create_platform_default_asset_io, AssetIo, AssetIoError},
+    prelude::*,
+    utils::BoxedFuture,
+};
+use flate2::read::ZlibDecoder;
+use image::{DynamicImage, ImageOutputFormat, RgbaImage};
+
+use crate::pack;
+
+const IMAGE_BANK: u16 = 0x6666;
+const SOUND_BANK: u16 = 0x6668;
+const LAST: u16 = 0x7F7F;
+
+const IMAGE_RLE: u8 = 0x1 | 0x2 | 0x4;
+const IMAGE_LZX: u8 = 0x8;
+const IMAGE_ALPHA: u8 = 0x10;
+
+const SOUND_UNCOMPRESSED: u32 = 33;
+
+/// Serves images and sounds straight from the original game's data pack
+/// (the Clickteam `.exe` or `.dat`), in place of the embedded `GameAssetsIo`.
+///
+/// `images/<frame>/<n>.png` is image `n` of the image bank, sheets with a
+/// file recipe in `pack` are assembled from it, and `sounds/<name>.wav` is
+/// the sound called `name`. Everything else, like layouts, is read from the
+/// usual asset folder.
+pub struct PackAssetIo {
+    pack: Pack,
+    fallback: Box<dyn AssetIo>,
+}
+
+impl PackAssetIo {
+    pub fn open(app: &mut App, path: &Path) -> Result<PackAssetIo, String> {
+        let pack = Pack::open(path)?;
+        info!(
+            "game data: {} images, {} sounds",
+            pack.images.len(),
+            pack.sounds.len()
+        );
+
+        Ok(PackAssetIo {
+            pack,
+            fallback: create_platform_default_asset_io(app),
+        })
+    }
+}
+
+impl AssetIo for PackAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            match self.pack.load(path) {
+                Some(Ok(bytes)) => Ok(bytes),
+                Some(Err(e)) => Err(AssetIoError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    e,
+                ))),
+                None => self.fallback.load_path(path).await,
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        self.fallback.read_directory(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.fallback.is_directory(path)
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.fallback.watch_path_for_changes(path)
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.fallback.watch_for_changes()
+    }
+}
+
+struct SoundEntry {
+    offset: usize,
+    len: usize,
+    compressed: bool,
+    name_length: usize,
+}
+
+/// Index of the image and sound banks of a data pack. Entries are only
+/// decompressed when loaded.
+pub struct Pack {
+    data: Vec<u8>,
+    /// Offset of each compressed image by handle.
+    images: HashMap<u32, usize>,
+    /// Sounds by lowercase name.
+    sounds: HashMap<String, SoundEntry>,
+}
+
+impl Pack {
+    pub fn open(path: &Path) -> Result<Pack, String> {
+        let data = std::fs::read(path).map_err(|e| e.to_string())?;
+        Pack::parse(data)
+    }
+
+    pub fn parse(data: Vec<u8>) -> Result<Pack, String> {
+        let start = find(&data, b"PAMU")
+            .or_else(|| find(&data, b"PAME"))
+            .ok_or("no Clickteam game header")?;
+
+        let mut images = HashMap::new();
+        let mut sounds = HashMap::new();
+
+        // magic, runtime version and subversion, product version and build
+        let mut r = Reader::new(&data, start + 16);
+        loop {
+            let id = r.u16()?;
+            let flags = r.u16()?;
+            let size = r.u32()? as usize;
+            let body = r.pos;
+            r.skip(size)?;
+
+            match id {
+                IMAGE_BANK | SOUND_BANK if flags != 0 => {
+                    return Err(format!(
+                        "bank chunk {:#x} has unsupported flags {}",
+                        id, flags
+                    ))
+                }
+                IMAGE_BANK => index_images(&data, body, &mut images)?,
+                SOUND_BANK => index_sounds(&data, body, &mut sounds)?,
+                LAST => break,
+                _ => {}
+            }
+        }
+
+        Ok(Pack {
+            data,
+            images,
+            sounds,
+        })
+    }
+
+    /// `None` if the path isn't something the pack provides.
+    pub fn load(&self, path: &Path) -> Option<Result<Vec<u8>, String>> {
+        let stem = path.file_stem()?.to_str()?;
+
+        match path.extension()?.to_str()? {
+            "png" => {
+                if let Some(handle) = image_handle(path) {
+                    self.images.get(&handle)?;
+                    return Some(self.image(handle).and_then(encode));
+                }
+
+                pack::pack_files(stem, |frame| match image_handle(frame) {
+                    Some(handle) => self.image(handle),
+                    None => Err(format!("{} isn't an image bank frame", frame.display())),
+                })
+                .map(|sheet| sheet.and_then(|sheet| encode(DynamicImage::ImageRgba8(sheet))))
+            }
+            "wav" => {
+                let entry = self.sounds.get(&stem.to_lowercase())?;
+                Some(self.sound(entry))
+            }
+            _ => None,
+        }
+    }
+
+    fn image(&self, handle: u32) -> Result<DynamicImage, String> {
+        let offset = *self
+            .images
+            .get(&handle)
+            .ok_or_else(|| format!("no image {}", handle))?;
+        let data = inflate(Reader::new(&self.data, offset))?;
+
+        let mut r = Reader::new(&data, 0);
+        // checksum, references
+        r.skip(8)?;
+        let size = r.u32()? as usize;
+        let width = r.u16()? as usize;
+        let height = r.u16()? as usize;
+        let mode = r.u8()?;
+        let flags = r.u8()?;
+        // reserved, hot spot, action point
+        r.skip(10)?;
+        let transparent = r.bytes(4)?;
+        let transparent = [transparent[0], transparent[1], transparent[2]];
+
+        if flags & IMAGE_RLE != 0 {
+            return Err(format!("image {} is RLE compressed", handle));
+        }
+
+        let pixels = match flags & IMAGE_LZX {
+            0 => r.bytes(size)?.to_vec(),
+            _ => {
+                r.skip(4)?;
+                let mut out = Vec::new();
+                let size = size
+                    .checked_sub(4)
+                    .ok_or_else(|| format!("image {} has a bad size", handle))?;
+                ZlibDecoder::new(r.bytes(size)?)
+                    .read_to_end(&mut out)
+                    .map_err(|e| e.to_string())?;
+                out
+            }
+        };
+
+        let point = match mode {
+            4 => 3,
+            6 | 7 => 2,
+            _ => return Err(format!("image {} uses unsupported mode {}", handle, mode)),
+        };
+        let row = (width + (2 - (width * point) % 2) % 2) * point;
+        let alpha_row = width + (4 - width % 4) % 4;
+        let alpha = match flags & IMAGE_ALPHA {
+            0 => None,
+            _ => Some(row * height),
+        };
+
+        let mut image = RgbaImage::new(width as u32, height as u32);
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * row + x * point;
+                let p = pixels
+                    .get(i..i + point)
+                    .ok_or_else(|| format!("image {} is truncated", handle))?;
+                let rgb = match mode {
+                    4 => [p[2], p[1], p[0]],
+                    6 => {
+                        let v = u16::from_le_bytes([p[0], p[1]]);
+                        [
+                            ((v >> 10) & 0x1F) as u8 * 8,
+                            ((v >> 5) & 0x1F) as u8 * 8,
+                            (v & 0x1F) as u8 * 8,
+                        ]
+                    }
+                    _ => {
+                        let v = u16::from_le_bytes([p[0], p[1]]);
+                        [
+                            ((v >> 11) & 0x1F) as u8 * 8,
+                            ((v >> 5) & 0x3F) as u8 * 4,
+                            (v & 0x1F) as u8 * 8,
+                        ]
+                    }
+                };
+                let a = match alpha {
+                    Some(start) => *pixels.get(start + y * alpha_row + x).unwrap_or(&255),
+                    None if rgb == transparent => 0,
+                    None => 255,
+                };
+
+                image.put_pixel(x as u32, y as u32, image::Rgba([rgb[0], rgb[1], rgb[2], a]));
+            }
+        }
+
+        Ok(DynamicImage::ImageRgba8(image))
+    }
+
+    fn sound(&self, entry: &SoundEntry) -> Result<Vec<u8>, String> {
+        let body = &self.data[entry.offset..entry.offset + entry.len];
+        let mut data = match entry.compressed {
+            true => inflate_raw(body)?,
+            false => body.to_vec(),
+        };
+
+        // the name comes before the file
+        data.drain(..(entry.name_length * 2).min(data.len()));
+        Ok(data)
+    }
+}
+
+fn index_images(data: &[u8], body: usize, images: &mut HashMap<u32, usize>) -> Result<(), String> {
+    let mut r = Reader::new(data, body);
+    for _ in 0..r.u32()? {
+        let handle = r.u32()?;
+        images.insert(handle, r.pos);
+        // decompressed size, compressed size
+        r.skip(4)?;
+        let compressed = r.u32()? as usize;
+        r.skip(compressed)?;
+    }
+
+    Ok(())
+}
+
+fn index_sounds(
+    data: &[u8],
+    body: usize,
+    sounds: &mut HashMap<String, SoundEntry>,
+) -> Result<(), String> {
+    let mut r = Reader::new(data, body);
+    for _ in 0..r.u32()? {
+        // handle, checksum, references
+        r.skip(12)?;
+        let decompressed = r.u32()? as usize;
+        let flags = r.u32()?;
+        // reserved
+        r.skip(4)?;
+        let name_length = r.u32()? as usize;
+
+        let compressed = flags != SOUND_UNCOMPRESSED;
+        let len = match compressed {
+            true => r.u32()? as usize,
+            false => decompressed,
+        };
+        let entry = SoundEntry {
+            offset: r.pos,
+            len,
+            compressed,
+            name_length,
+        };
+        r.skip(len)?;
+
+        let head = match compressed {
+            true => {
+                let mut head = vec![0; name_length * 2];
+                ZlibDecoder::new(&data[entry.offset..entry.offset + len])
+                    .read_exact(&mut head)
+                    .map_err(|e| e.to_string())?;
+                head
+            }
+            false => Reader::new(data, entry.offset)
+                .bytes(name_length * 2)?
+                .to_vec(),
+        };
+        let name: Vec<u16> = head
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .take_while(|c| *c != 0)
+            .collect();
+
+        sounds.insert(String::from_utf16_lossy(&name).to_lowercase(), entry);
+    }
+
+    Ok(())
+}
+
+/// `images/Title/430.png` is image 430.
+fn image_handle(path: &Path) -> Option<u32> {
+    path.file_stem()?.to_str()?.parse().ok()
+}
+
+fn encode(image: DynamicImage) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    image
+        .write_to(&mut out, ImageOutputFormat::Png)
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+/// A decompressed size and compressed size followed by a zlib stream.
+fn inflate(mut r: Reader) -> Result<Vec<u8>, String> {
+    r.skip(4)?;
+    let len = r.u32()? as usize;
+    inflate_raw(r.bytes(len)?)
+}
+
+fn inflate_raw(bytes: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    ZlibDecoder::new(bytes)
+        .read_to_end(&mut out)
+        .map_err(|e| e.to_string())?;
+    Ok(out)
+}
+
+fn find(data: &[u8], needle: &[u8]) -> Option<usize> {
+    data.windows(needle.len()).position(|w| w == needle)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8], pos: usize) -> Reader<'a> {
+        Reader { data, pos }
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], String> {
+        let bytes = self
+            .data
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| format!("unexpected end of data at {:#x}", self.pos))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), String> {
+        self.bytes(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::ZlibEncoder, Compression};
+
+    use super::*;
+
+    fn zlib(bytes: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(bytes).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn chunk(id: u16, flags: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend(id.to_le_bytes());
+        out.extend(flags.to_le_bytes());
+        out.extend((body.len() as u32).to_le_bytes());
+        out.extend(body);
+        out
+    }
+
+    /// A 1x1 image, `flags` and `size` as in its header.
+    fn image(handle: u32, flags: u8, size: u32, pixels: &[u8]) -> Vec<u8> {
+        let mut header = vec![0; 8];
+        header.extend(size.to_le_bytes());
+        header.extend(1u16.to_le_bytes());
+        header.extend(1u16.to_le_bytes());
+        header.extend([4, flags]);
+        header.extend([0; 10]);
+        header.extend([0, 0, 0, 0]);
+        header.extend(pixels);
+
+        let compressed = zlib(&header);
+        let mut bank = Vec::new();
+        bank.extend(1u32.to_le_bytes());
+        bank.extend(handle.to_le_bytes());
+        bank.extend((header.len() as u32).to_le_bytes());
+        bank.extend((compressed.len() as u32).to_le_bytes());
+        bank.extend(compressed);
+        bank
+    }
+
+    /// An uncompressed sound called `name`.
+    fn sound(name: &str, wav: &[u8]) -> Vec<u8> {
+        let name: Vec<u8> = name
+            .encode_utf16()
+            .chain([0])
+            .flat_map(|c| c.to_le_bytes())
+            .collect();
+        let mut bank = Vec::new();
+        bank.extend(1u32.to_le_bytes());
+        bank.extend([0; 12]);
+        bank.extend(((name.len() + wav.len()) as u32).to_le_bytes());
+        bank.extend(SOUND_UNCOMPRESSED.to_le_bytes());
+        bank.extend([0; 4]);
+        bank.extend(((name.len() / 2) as u32).to_le_bytes());
+        bank.extend(name);
+        bank.extend(wav);
+        bank
+    }
+
+    fn pack(chunks: &[Vec<u8>]) -> Vec<u8> {
+        // something before the header, like the executable
+        let mut data = b"MZ\0\0".to_vec();
+        data.extend(b"PAMU");
+        data.extend([0; 12]);
+        for c in chunks {
+            data.extend(c);
+        }
+        data.extend(chunk(LAST, 0, &[]));
+        data
+    }
+
+    #[test]
+    fn reads_images_and_sounds() {
+        // BGR and padding to an even row
+        let pixels = [0x30, 0x20, 0x10, 0, 0, 0];
+        let data = pack(&[
+            chunk(0x2223, 0, b"skipped"),
+            chunk(IMAGE_BANK, 0, &image(430, 0, 6, &pixels)),
+            chunk(SOUND_BANK, 0, &sound("Knock2", b"RIFF")),
+        ]);
+        let pack = Pack::parse(data).unwrap();
+
+        let png = pack
+            .load(Path::new("images/Title/430.png"))
+            .unwrap()
+            .unwrap();
+        let image = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(image.get_pixel(0, 0).0, [0x10, 0x20, 0x30, 255]);
+
+        let wav = pack.load(Path::new("sounds/knock2.wav")).unwrap().unwrap();
+        assert_eq!(wav, b"RIFF");
+
+        assert!(pack.load(Path::new("images/Title/431.png")).is_none());
+        assert!(pack.load(Path::new("layouts/title.layout.toml")).is_none());
+    }
+
+    #[test]
+    fn rejects_a_bad_image_size() {
+        let data = pack(&[chunk(IMAGE_BANK, 0, &image(1, IMAGE_LZX, 2, &[0, 0, 0, 0]))]);
+        let pack = Pack::parse(data).unwrap();
+
+        assert!(pack.load(Path::new("images/Title/1.png")).unwrap().is_err());
+    }
+
+    #[test]
+    fn rejects_broken_packs() {
+        assert!(Pack::parse(b"not a game".to_vec()).is_err());
+        assert!(Pack::parse(pack(&[chunk(IMAGE_BANK, 1, &[])])).is_err());
+
+        // cut off in the middle of a chunk
+        let mut data = pack(&[chunk(SOUND_BANK, 0, &sound("a", b"RIFF"))]);
+        data.truncate(data.len() - 12);
+        assert!(Pack::parse(data).is_err());
+    }
+}
@@ -1,14 +1,197 @@
+use std::fmt::Display;
+
 use bevy::prelude::*;
+use serde::Deserialize;
 
+/// Numbers drawn from a digit atlas: the night number, the clock hour, the
+/// power left and the custom night AI levels.
 pub struct CounterPlugin;
 
 impl Plugin for CounterPlugin {
     fn build(&self, app: &mut App) {
-        todo!()
+        app.add_system(redraw::<u8>.label(Redraw))
+            .add_system(redraw::<u32>.label(Redraw))
+            .add_system(show::<u8>.after(Redraw))
+            .add_system(show::<u32>.after(Redraw));
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Align {
+    /// First digit starts at the counter's origin.
+    #[default]
+    Left,
+    /// Last digit, or the suffix, ends at the counter's origin.
+    Right,
+}
+
+/// A multi-digit number. Frames 0 to 9 of the atlas are the digits; the
+/// digits are respawned as children whenever the counter changes, so spawn it
+/// with a `TransformBundle` placing the number. The digits are shown and
+/// hidden with the counter's `Visibility`, if it has one.
+#[derive(Component)]
+pub struct Counter<T: Display + PartialEq + Send + Sync + 'static> {
+    pub texture_atlas: Handle<TextureAtlas>,
+    pub value: T,
+    /// Horizontal distance between digits, in pixels.
+    pub advance: f32,
+    /// Minimum number of digits shown.
+    pub digits: usize,
+    /// Pad up to `digits` with zeros instead of blank space.
+    pub leading_zeros: bool,
+    pub align: Align,
+    /// Atlas frame drawn after the last digit, such as a "%".
+    pub suffix: Option<usize>,
+}
+
+impl<T: Display + PartialEq + Send + Sync + 'static> Counter<T> {
+    pub fn new(texture_atlas: Handle<TextureAtlas>, value: T, advance: f32) -> Counter<T> {
+        Counter {
+            texture_atlas,
+            value,
+            advance,
+            digits: 1,
+            leading_zeros: false,
+            align: Align::Left,
+            suffix: None,
+        }
+    }
+
+    pub fn align(mut self, align: Align) -> Counter<T> {
+        self.align = align;
+        self
+    }
+
+    /// Atlas frames to draw, left to right, with `None` for blank padding.
+    fn frames(&self) -> Vec<Option<usize>> {
+        let text = self.value.to_string();
+        let mut frames: Vec<Option<usize>> = text
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .map(|d| Some(d as usize))
+            .collect();
+
+        let pad = self.digits.saturating_sub(frames.len());
+        let fill = match self.leading_zeros {
+            true => Some(0),
+            false => None,
+        };
+        frames.splice(0..0, vec![fill; pad]);
+        frames.extend(self.suffix.map(Some));
+        frames
+    }
+}
+
+/// Keeps a counter showing a value read from the resource `R`.
+#[derive(Component)]
+pub struct Bind<R: Send + Sync + 'static, T: Send + Sync + 'static>(pub fn(&R) -> T);
+
+pub trait CounterApp {
+    /// Updates every counter with a `Bind<R, T>` when `R` changes.
+    fn bind_counter<R: Send + Sync + 'static, T: Display + PartialEq + Send + Sync + 'static>(
+        &mut self,
+    ) -> &mut Self;
+}
+
+impl CounterApp for App {
+    fn bind_counter<R: Send + Sync + 'static, T: Display + PartialEq + Send + Sync + 'static>(
+        &mut self,
+    ) -> &mut Self {
+        self.add_system(follow::<R, T>.before(Redraw))
     }
 }
 
-pub struct Counter<T: std::fmt::Display + PartialEq + Eq + PartialOrd + Ord> {
-    texture_atlas: Handle<TextureAtlas>,
-    index: T,
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+struct Redraw;
+
+type Bound<'a, R, T> = (
+    &'a Bind<R, T>,
+    &'a mut Counter<T>,
+    ChangeTrackers<Bind<R, T>>,
+);
+
+fn follow<R: Send + Sync + 'static, T: Display + PartialEq + Send + Sync + 'static>(
+    resource: Res<R>,
+    mut query: Query<Bound<R, T>>,
+) {
+    for (bind, mut counter, tracker) in query.iter_mut() {
+        if resource.is_changed() || tracker.is_added() {
+            let value = (bind.0)(&resource);
+            // Only write through `Mut` on a real change, or it redraws.
+            if counter.value != value {
+                counter.value = value;
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+struct Digit;
+
+type Drawn<'a, T> = (
+    Entity,
+    &'a Counter<T>,
+    Option<&'a Children>,
+    Option<&'a Visibility>,
+);
+
+fn redraw<T: Display + PartialEq + Send + Sync + 'static>(
+    mut commands: Commands,
+    counters: Query<Drawn<T>, Changed<Counter<T>>>,
+    digits: Query<(), With<Digit>>,
+) {
+    for (entity, counter, children, visibility) in counters.iter() {
+        if let Some(children) = children {
+            for &child in children.iter() {
+                if digits.get(child).is_ok() {
+                    commands.entity(child).despawn_recursive();
+                }
+            }
+        }
+
+        let frames = counter.frames();
+        let width = counter.advance * frames.len() as f32;
+        let start = match counter.align {
+            Align::Left => 0.0,
+            Align::Right => -width,
+        };
+
+        commands.entity(entity).with_children(|parent| {
+            for (i, frame) in frames.into_iter().enumerate() {
+                let index = match frame {
+                    Some(index) => index,
+                    None => continue,
+                };
+
+                // Sprites are centred on their translation.
+                let x = start + counter.advance * (i as f32 + 0.5);
+                parent
+                    .spawn_bundle(SpriteSheetBundle {
+                        texture_atlas: counter.texture_atlas.clone(),
+                        sprite: TextureAtlasSprite::new(index),
+                        transform: Transform::from_xyz(x, 0.0, 0.0),
+                        visibility: visibility.cloned().unwrap_or_default(),
+                        ..default()
+                    })
+                    .insert(Digit);
+            }
+        });
+    }
+}
+
+type Shown<T> = (With<Counter<T>>, Changed<Visibility>);
+
+/// Visibility isn't inherited, so hand the counter's down to its digits.
+fn show<T: Display + PartialEq + Send + Sync + 'static>(
+    counters: Query<(&Visibility, &Children), Shown<T>>,
+    mut digits: Query<&mut Visibility, (With<Digit>, Without<Counter<T>>)>,
+) {
+    for (visibility, children) in counters.iter() {
+        for &child in children.iter() {
+            if let Ok(mut digit) = digits.get_mut(child) {
+                digit.is_visible = visibility.is_visible;
+            }
+        }
+    }
 }
@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+
+use crate::{
+    despawn_screen, locale::Locale, night::Dawn, save::Config, transition::Transition,
+    tuning::Tuning, GameState,
+};
+
+/// The screens between nights: the night's number before it, and 6 AM once
+/// it's survived, which saves the progress. They are text until the
+/// original's animations are in, and the endings aren't, so beating night 5
+/// or later goes back to the title.
+pub struct DayPlugin;
+
+impl Plugin for DayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(SystemSet::on_update(GameState::Frame1).with_system(dawn));
+        for state in [GameState::WhatDay, GameState::NextDay] {
+            app.add_system_set(SystemSet::on_update(state.clone()).with_system(wait))
+                .add_system_set(
+                    SystemSet::on_exit(state).with_system(despawn_screen::<OnDayScreen>),
+                );
+        }
+        app.add_system_set(SystemSet::on_enter(GameState::WhatDay).with_system(what_day))
+            .add_system_set(SystemSet::on_enter(GameState::NextDay).with_system(next_day));
+    }
+}
+
+#[derive(Component)]
+struct OnDayScreen;
+
+/// Where the screen goes once its time is up.
+struct DayScreen {
+    timer: Timer,
+    to: GameState,
+}
+
+fn dawn(mut dawns: EventReader<Dawn>, mut transitions: EventWriter<Transition>) {
+    if dawns.iter().next().is_some() {
+        transitions.send(Transition::To(GameState::NextDay));
+    }
+}
+
+fn what_day(commands: Commands, config: Res<Config>, locale: Res<Locale>, tuning: Res<Tuning>) {
+    let night = config.level().to_string();
+    let text = locale.fill("day.night", &[("night", &night)]);
+    let screen = DayScreen {
+        timer: Timer::from_seconds(tuning.days.what_day, false),
+        to: GameState::Frame1,
+    };
+    spawn(commands, &locale, text, screen);
+}
+
+fn next_day(
+    commands: Commands,
+    mut config: ResMut<Config>,
+    locale: Res<Locale>,
+    tuning: Res<Tuning>,
+) {
+    let to = match config.level() {
+        level @ 1..=4 => {
+            config.set_level(level + 1);
+            GameState::WhatDay
+        }
+        5 => {
+            config.set_beatgame(true);
+            GameState::Title
+        }
+        // the 6th and custom nights are picked on the title, continuing
+        // goes back to the 5th
+        6 => {
+            config.set_beat_six(true);
+            config.set_level(5);
+            GameState::Title
+        }
+        _ => {
+            config.set_beat_seven(true);
+            config.set_level(5);
+            GameState::Title
+        }
+    };
+    let screen = DayScreen {
+        timer: Timer::from_seconds(tuning.days.next_day, false),
+        to,
+    };
+    spawn(
+        commands,
+        &locale,
+        locale.text("day.dawn").to_string(),
+        screen,
+    );
+}
+
+fn spawn(mut commands: Commands, locale: &Locale, text: String, screen: DayScreen) {
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(OnDayScreen);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(OnDayScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    text,
+                    TextStyle {
+                        font: locale.font.clone(),
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                    },
+                    TextAlignment {
+                        horizontal: HorizontalAlign::Center,
+                        ..default()
+                    },
+                ),
+                ..default()
+            });
+        });
+    commands.insert_resource(screen);
+}
+
+/// Outlasts the fade in, so the transition isn't asked for while one is on.
+fn wait(
+    time: Res<Time>,
+    screen: Option<ResMut<DayScreen>>,
+    mut transitions: EventWriter<Transition>,
+) {
+    // inserted by the screen's setup, once its commands are applied
+    let mut screen = match screen {
+        Some(screen) => screen,
+        None => return,
+    };
+    if screen.timer.tick(time.delta()).just_finished() {
+        transitions.send(Transition::To(screen.to.clone()));
+    }
+}
@@ -0,0 +1,200 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use crate::{
+    doors::Doors,
+    locale::Locale,
+    map::{Monitor, Room},
+    mods::{script::Actors, Mods},
+    night::{Clock, Power, RATE},
+    save::Config,
+    transition::Transition,
+    tuning::Tuning,
+    GameState,
+};
+
+/// A developer overlay for balancing nights, only built into debug builds
+/// and with `--features dev`. The keys are fixed, they aren't bindings:
+///
+/// - F3 shows or hides it.
+/// - F6 picks the next animatronic, F7 teleports it to the next room.
+/// - F8 skips to the next hour.
+/// - F9 forces the next state out of `STATES`.
+pub struct DebugPlugin;
+
+impl Plugin for DebugPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<DebugOverlay>()
+            .add_startup_system(setup)
+            .add_system(keys)
+            .add_system(render.after(keys));
+    }
+}
+
+/// States F9 cycles through, the ones that have a screen.
+const STATES: [GameState; 5] = [
+    GameState::Frame17,
+    GameState::Title,
+    GameState::Frame1,
+    GameState::Trophies,
+    GameState::Extras,
+];
+
+#[derive(Default)]
+struct DebugOverlay {
+    shown: bool,
+    /// Index into `Actors`.
+    selected: usize,
+}
+
+#[derive(Component)]
+struct Overlay;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    top: Val::Px(8.0),
+                    left: Val::Px(8.0),
+                    ..default()
+                },
+                ..default()
+            },
+            visibility: Visibility { is_visible: false },
+            ..default()
+        })
+        .insert(Overlay);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn keys(
+    keys: Res<Input<KeyCode>>,
+    mut debug: ResMut<DebugOverlay>,
+    mut clock: ResMut<Clock>,
+    mut actors: ResMut<Actors>,
+    mut doors: ResMut<Doors>,
+    state: Res<State<GameState>>,
+    mut transitions: EventWriter<Transition>,
+    tuning: Res<Tuning>,
+) {
+    if keys.just_pressed(KeyCode::F3) {
+        debug.shown = !debug.shown;
+    }
+
+    let count = actors.actors.len().max(1);
+    if keys.just_pressed(KeyCode::F6) {
+        debug.selected = (debug.selected + 1) % count;
+    }
+    if keys.just_pressed(KeyCode::F7) {
+        if let Some(actor) = actors.actors.get_mut(debug.selected) {
+            let i = Room::ALL.iter().position(|r| *r == actor.room).unwrap_or(0);
+            // through the doors, so getting in jams them and attacks like a
+            // real move
+            let attack = (tuning.office.attack * RATE as f32) as u64;
+            actor.enter(Room::ALL[(i + 1) % Room::ALL.len()], &mut doors, attack);
+        }
+    }
+
+    if keys.just_pressed(KeyCode::F8) && clock.hour() < 6 {
+        // the tick before the hour, so the night step still sees it strike
+        clock.tick = (clock.hour() as u64 + 1) * clock.length - 1;
+    }
+
+    if keys.just_pressed(KeyCode::F9) {
+        let i = STATES.iter().position(|s| s == state.current());
+        let next = STATES[i.map_or(0, |i| (i + 1) % STATES.len())].clone();
+        info!("debug: forcing {:?}", next);
+        transitions.send(Transition::Force(next));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render(
+    debug: Res<DebugOverlay>,
+    locale: Res<Locale>,
+    state: Res<State<GameState>>,
+    clock: Res<Clock>,
+    power: Res<Power>,
+    doors: Res<Doors>,
+    monitor: Res<Monitor>,
+    actors: Res<Actors>,
+    config: Res<Config>,
+    mods: Res<Mods>,
+    mut shown: Local<String>,
+    mut overlay: Query<(&mut Text, &mut Visibility), With<Overlay>>,
+) {
+    let (mut text, mut visibility) = match overlay.get_single_mut() {
+        Ok(overlay) => overlay,
+        Err(_) => return,
+    };
+    if visibility.is_visible != debug.shown {
+        visibility.is_visible = debug.shown;
+    }
+    if !debug.shown {
+        return;
+    }
+
+    let seconds = |ticks: u64| ticks as f32 / RATE as f32;
+    let hour = clock.hour();
+    // what `night::drain` takes a tick, scripts drain on top of it
+    let drain = match config.cheats().infinite_power {
+        true => 0.0,
+        false => Power::rate(&doors, &monitor, mods.drain(config.level())) * RATE as f32,
+    };
+    let mut out = String::new();
+    writeln!(out, "state  {:?}", state.current()).unwrap();
+    writeln!(
+        out,
+        "clock  {:.1}s  hour {}  ({:.1}s an hour)",
+        seconds(clock.tick),
+        hour,
+        seconds(clock.length)
+    )
+    .unwrap();
+    writeln!(out, "power  {:.3}%  -{:.3}%/s", power.0, drain).unwrap();
+
+    let mark = |i: usize| match i == debug.selected {
+        true => '>',
+        false => ' ',
+    };
+    for (i, actor) in actors.actors.iter().enumerate() {
+        let next = match actor.attack {
+            Some(ticks) => format!("attacks in {:.1}s", seconds(ticks)),
+            None => format!("move in {:.1}s", seconds(actor.every - clock.tick % actor.every)),
+        };
+        let roll = match actor.roll {
+            Some((value, ai)) => format!("rolled {} vs {}", value, ai),
+            None => "no roll".to_string(),
+        };
+        writeln!(
+            out,
+            "{} {:<8} {}  ai {}  {}  {}  drained {:.1}%",
+            mark(i),
+            actor.name,
+            actor.room.name(),
+            actor.ai(hour),
+            next,
+            roll,
+            actor.drained
+        )
+        .unwrap();
+    }
+
+    // the clock shows tenths of a second, no need to lay the text out every frame
+    if *shown == out && !locale.is_changed() {
+        return;
+    }
+    *text = Text::with_section(
+        out.clone(),
+        TextStyle {
+            font: locale.font.clone(),
+            font_size: 16.0,
+            color: Color::YELLOW,
+        },
+        default(),
+    );
+    *shown = out;
+}
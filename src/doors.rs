@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    assets::GameAssets,
+    audio::{Channel, PlaySound},
+    input::Action,
+    map::{Animatronic, Positions, Room},
+    mods::script::Actors,
+    office::Office,
+    save::Config,
+    tuning::Tuning,
+    warp::WarpMaterial,
+    GameState,
+};
+
+/// The doors, the hall lights and who is standing in the doorways.
+pub struct DoorPlugin;
+
+impl Plugin for DoorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Doors>()
+            .add_event::<Jumpscare>()
+            .add_system_set(SystemSet::on_enter(GameState::Frame1).with_system(reset))
+            .add_system_set(
+                SystemSet::on_update(GameState::Frame1)
+                    .with_system(buttons.before(press))
+                    .with_system(press)
+                    .with_system(view.after(press)),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+impl Side {
+    pub const ALL: [Side; 2] = [Side::Left, Side::Right];
+
+    /// The blind spot outside this door.
+    pub fn doorway(self) -> Room {
+        match self {
+            Side::Left => Room::LeftDoor,
+            Side::Right => Room::RightDoor,
+        }
+    }
+
+    /// Who comes to this door and shows up in its window.
+    pub fn visitor(self) -> Animatronic {
+        match self {
+            Side::Left => Animatronic::Bonnie,
+            Side::Right => Animatronic::Chica,
+        }
+    }
+}
+
+/// Clickable door button in the office.
+#[derive(Component)]
+pub struct DoorButton(pub Side);
+
+/// Clickable light button in the office.
+#[derive(Component)]
+pub struct LightButton(pub Side);
+
+/// Sent when an animatronic in the office attacks. Holds its name.
+pub struct Jumpscare(pub String);
+
+#[derive(Clone, Copy, Default, Debug, PartialEq, Eq)]
+pub struct Door {
+    pub closed: bool,
+    pub light: bool,
+    /// Someone got in while this door was open: no door or light button
+    /// does anything until they attack.
+    pub jammed: bool,
+    /// The window sting already played for whoever is in the doorway.
+    pub seen: bool,
+}
+
+/// Both sides of the office. The rules live here so the office view and the
+/// AI agree on them.
+#[derive(Default, Debug)]
+pub struct Doors([Door; 2]);
+
+impl Doors {
+    pub fn get(&self, side: Side) -> &Door {
+        &self.0[side as usize]
+    }
+
+    fn get_mut(&mut self, side: Side) -> &mut Door {
+        &mut self.0[side as usize]
+    }
+
+    /// Whether someone got in, which stops every button working.
+    pub fn jammed(&self) -> bool {
+        self.0.iter().any(|door| door.jammed)
+    }
+
+    pub fn toggle_door(&mut self, side: Side) {
+        if !self.jammed() {
+            let door = self.get_mut(side);
+            door.closed = !door.closed;
+        }
+    }
+
+    /// Only one hall light is on at a time.
+    pub fn toggle_light(&mut self, side: Side) {
+        if self.jammed() {
+            return;
+        }
+
+        let on = !self.get(side).light;
+        for door in self.0.iter_mut() {
+            door.light = false;
+        }
+        self.get_mut(side).light = on;
+    }
+
+    /// The visitor steps from the doorway into the office. Returns whether
+    /// they got in; a closed door sends them back.
+    pub fn enter(&mut self, side: Side) -> bool {
+        let door = self.get_mut(side);
+        door.seen = false;
+        if door.closed {
+            return false;
+        }
+
+        door.jammed = true;
+        for door in self.0.iter_mut() {
+            door.light = false;
+        }
+        true
+    }
+
+    /// The visitor left the doorway without getting in.
+    pub fn leave(&mut self, side: Side) {
+        self.get_mut(side).seen = false;
+    }
+
+    /// Whoever got in attacked. Returns the side that was jammed, if any.
+    pub fn clear_jam(&mut self) -> Option<Side> {
+        let side = Side::ALL.into_iter().find(|s| self.get(*s).jammed)?;
+        for door in self.0.iter_mut() {
+            door.jammed = false;
+        }
+        Some(side)
+    }
+}
+
+fn reset(mut doors: ResMut<Doors>) {
+    *doors = Doors::default();
+}
+
+fn buttons(
+    doors: Query<(&Interaction, &DoorButton), Changed<Interaction>>,
+    lights: Query<(&Interaction, &LightButton), Changed<Interaction>>,
+    mut state: ResMut<Doors>,
+) {
+    for (interaction, button) in doors.iter() {
+        if *interaction == Interaction::Clicked {
+            state.toggle_door(button.0);
+        }
+    }
+    for (interaction, button) in lights.iter() {
+        if *interaction == Interaction::Clicked {
+            state.toggle_light(button.0);
+        }
+    }
+}
+
+fn press(actions: Res<Input<Action>>, mut doors: ResMut<Doors>) {
+    if actions.just_pressed(Action::LeftDoor) {
+        doors.toggle_door(Side::Left);
+    }
+    if actions.just_pressed(Action::RightDoor) {
+        doors.toggle_door(Side::Right);
+    }
+    if actions.just_pressed(Action::LeftLight) {
+        doors.toggle_light(Side::Left);
+    }
+    if actions.just_pressed(Action::RightLight) {
+        doors.toggle_light(Side::Right);
+    }
+}
+
+/// Whether the hall lights are dark for a flicker, rolled every
+/// `flicker_every` seconds.
+#[derive(Default)]
+struct Flicker {
+    timer: Timer,
+    dark: bool,
+}
+
+/// Picks the office image for the lights, flickering them, and plays the
+/// sting the first time a lit window shows someone.
+#[allow(clippy::too_many_arguments)]
+fn view(
+    asr: Res<AssetServer>,
+    time: Res<Time>,
+    tuning: Res<Tuning>,
+    config: Res<Config>,
+    positions: Res<Positions>,
+    actors: Res<Actors>,
+    mut doors: ResMut<Doors>,
+    mut materials: ResMut<Assets<WarpMaterial>>,
+    mut sounds: EventWriter<PlaySound>,
+    mut shown: Local<Option<&'static str>>,
+    mut flicker: Local<Flicker>,
+    office: Query<&Handle<WarpMaterial>, With<Office>>,
+) {
+    let odds = match config.accessibility().reduced_flashing {
+        true => 0.0,
+        false => tuning.office.flicker,
+    };
+    flicker.timer.tick(time.delta());
+    if flicker.timer.finished() {
+        flicker.timer = Timer::from_seconds(tuning.office.flicker_every, false);
+        flicker.dark = rand::thread_rng().gen::<f32>() < odds;
+    }
+    let lit = Side::ALL
+        .into_iter()
+        .find(|side| doors.get(*side).light)
+        .filter(|_| !flicker.dark);
+
+    let image = match lit {
+        None => GameAssets::Office,
+        Some(side) => {
+            let visitor = positions.room(side.visitor()) == side.doorway();
+            let shown = actors.actors.iter().any(|a| a.shows_at(side));
+            if shown && !doors.get(side).seen {
+                doors.get_mut(side).seen = true;
+                sounds.send(PlaySound {
+                    channel: Channel::Sfx,
+                    sound: load!(asr, WindowScare),
+                    looped: false,
+                });
+            }
+
+            match (side, visitor) {
+                (Side::Left, false) => GameAssets::OfficeLeftLight,
+                (Side::Left, true) => GameAssets::OfficeLeftLightBonnie,
+                (Side::Right, false) => GameAssets::OfficeRightLight,
+                (Side::Right, true) => GameAssets::OfficeRightLightChica,
+            }
+        }
+    };
+
+    if *shown == Some(image.file()) {
+        return;
+    }
+    for handle in office.iter() {
+        if let Some(material) = materials.get_mut(handle) {
+            material.texture = asr.load(image.file());
+        }
+    }
+    *shown = Some(image.file());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn getting_in_jams_the_buttons() {
+        let mut doors = Doors::default();
+        doors.toggle_light(Side::Left);
+
+        assert!(doors.enter(Side::Left));
+        assert!(doors.jammed());
+        assert!(doors.get(Side::Left).jammed);
+        assert!(Side::ALL.iter().all(|side| !doors.get(*side).light));
+    }
+
+    #[test]
+    fn jammed_buttons_do_nothing() {
+        let mut doors = Doors::default();
+        doors.enter(Side::Right);
+
+        for side in Side::ALL {
+            doors.toggle_door(side);
+            doors.toggle_light(side);
+            assert_eq!(
+                *doors.get(side),
+                Door {
+                    jammed: side == Side::Right,
+                    ..default()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn attacking_clears_the_jam() {
+        let mut doors = Doors::default();
+        assert_eq!(doors.clear_jam(), None);
+
+        doors.enter(Side::Right);
+        assert_eq!(doors.clear_jam(), Some(Side::Right));
+        assert!(!doors.jammed());
+        assert_eq!(doors.clear_jam(), None);
+
+        doors.toggle_door(Side::Left);
+        assert!(doors.get(Side::Left).closed);
+    }
+
+    #[test]
+    fn a_closed_door_keeps_them_out() {
+        let mut doors = Doors::default();
+        doors.toggle_door(Side::Left);
+        doors.toggle_light(Side::Left);
+
+        assert!(!doors.enter(Side::Left));
+        assert!(!doors.jammed());
+        assert!(doors.get(Side::Left).light);
+
+        doors.toggle_door(Side::Left);
+        assert!(!doors.get(Side::Left).closed);
+    }
+}
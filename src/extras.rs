@@ -0,0 +1,298 @@
+use bevy::prelude::*;
+
+use crate::{
+    assets::GameAssets, despawn_screen, doors::Jumpscare, input::Action, locale::Locale,
+    map::Animatronic, save::Config, transition::Transition, GameState,
+};
+
+/// The extras menu, opened from the title once the game is beaten: every
+/// shot of every animatronic, their jumpscares and the cheats.
+pub struct ExtrasPlugin;
+
+impl Plugin for ExtrasPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ExtrasMenu>()
+            .add_system_set(SystemSet::on_enter(GameState::Extras).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(GameState::Extras)
+                    .with_system(hover)
+                    .with_system(navigate.after(hover))
+                    .with_system(render.after(navigate)),
+            )
+            .add_system_set(
+                SystemSet::on_exit(GameState::Extras).with_system(despawn_screen::<OnExtrasScreen>),
+            );
+    }
+}
+
+/// The shots each animatronic is in, cycled through like turning it around.
+/// There are no renders of them besides the cameras and the office lights.
+fn views(animatronic: Animatronic) -> &'static [GameAssets] {
+    match animatronic {
+        Animatronic::Freddy => &[
+            GameAssets::CamShowStageFreddy,
+            GameAssets::CamShowStageFreddyStare,
+            GameAssets::CamDiningAreaFreddy,
+            GameAssets::CamRestroomsFreddy,
+            GameAssets::CamEastHallFreddy,
+            GameAssets::CamEastHallCornerFreddy,
+        ],
+        Animatronic::Bonnie => &[
+            GameAssets::CamDiningAreaBonnie,
+            GameAssets::CamBackstageBonnie,
+            GameAssets::CamBackstageBonnieStare,
+            GameAssets::CamWestHallBonnie,
+            GameAssets::CamSupplyClosetBonnie,
+            GameAssets::CamWestHallCornerBonnie,
+            GameAssets::OfficeLeftLightBonnie,
+        ],
+        Animatronic::Chica => &[
+            GameAssets::CamDiningAreaChica,
+            GameAssets::CamDiningAreaChicaClose,
+            GameAssets::CamRestroomsChica,
+            GameAssets::CamRestroomsChicaClose,
+            GameAssets::CamEastHallChica,
+            GameAssets::CamEastHallCornerChica,
+            GameAssets::OfficeRightLightChica,
+        ],
+        Animatronic::Foxy => &[GameAssets::CamPirateCove],
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Entry {
+    Animatronic,
+    View,
+    Jumpscare,
+    FastNights,
+    InfinitePower,
+    Radar,
+}
+
+impl Entry {
+    const ALL: [Entry; 6] = [
+        Entry::Animatronic,
+        Entry::View,
+        Entry::Jumpscare,
+        Entry::FastNights,
+        Entry::InfinitePower,
+        Entry::Radar,
+    ];
+
+    fn label(self, menu: &ExtrasMenu, config: &Config, locale: &Locale) -> String {
+        let toggle = |key, on: bool| {
+            let state = match on {
+                true => locale.text("pause.on"),
+                false => locale.text("pause.off"),
+            };
+            locale.fill(key, &[("state", state)])
+        };
+        let cheats = config.cheats();
+
+        match self {
+            Entry::Animatronic => locale.fill(
+                "extras.animatronic",
+                &[("name", locale.text(&name_key(menu.animatronic())))],
+            ),
+            Entry::View => locale.fill(
+                "extras.view",
+                &[
+                    ("view", &(menu.view + 1).to_string()),
+                    ("views", &views(menu.animatronic()).len().to_string()),
+                ],
+            ),
+            Entry::Jumpscare => locale.text("extras.jumpscare").to_string(),
+            Entry::FastNights => toggle("extras.fast_nights", cheats.fast_nights),
+            Entry::InfinitePower => toggle("extras.infinite_power", cheats.infinite_power),
+            Entry::Radar => toggle("extras.radar", cheats.radar),
+        }
+    }
+}
+
+fn name_key(animatronic: Animatronic) -> String {
+    format!("extras.{}", animatronic.name())
+}
+
+#[derive(Default)]
+struct ExtrasMenu {
+    selected: usize,
+    /// Index into `Animatronic::ALL`.
+    animatronic: usize,
+    /// Index into the animatronic's `views`.
+    view: usize,
+}
+
+impl ExtrasMenu {
+    fn animatronic(&self) -> Animatronic {
+        Animatronic::ALL[self.animatronic]
+    }
+}
+
+#[derive(Component)]
+struct OnExtrasScreen;
+
+#[derive(Component)]
+struct Menu;
+
+#[derive(Component)]
+struct Render;
+
+#[derive(Component)]
+struct Row(usize);
+
+fn setup(mut commands: Commands, mut menu: ResMut<ExtrasMenu>) {
+    *menu = ExtrasMenu::default();
+
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(OnExtrasScreen);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                justify_content: JustifyContent::SpaceAround,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: UiColor(Color::BLACK),
+            ..default()
+        })
+        .insert(OnExtrasScreen)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::ColumnReverse,
+                        align_items: AlignItems::FlexStart,
+                        ..default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..default()
+                })
+                .insert(Menu);
+            // a camera shot at half size
+            parent
+                .spawn_bundle(ImageBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(640.0), Val::Px(360.0)),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .insert(Render);
+        });
+}
+
+fn hover(rows: Query<(&Interaction, &Row), Changed<Interaction>>, mut menu: ResMut<ExtrasMenu>) {
+    for (interaction, row) in rows.iter() {
+        if *interaction != Interaction::None && menu.selected != row.0 {
+            menu.selected = row.0;
+        }
+    }
+}
+
+fn navigate(
+    actions: Res<Input<Action>>,
+    mut config: ResMut<Config>,
+    mut menu: ResMut<ExtrasMenu>,
+    mut transitions: EventWriter<Transition>,
+    mut jumpscares: EventWriter<Jumpscare>,
+    clicks: Query<&Interaction, (Changed<Interaction>, With<Row>)>,
+) {
+    let rows = Entry::ALL.len();
+    if actions.just_pressed(Action::Up) {
+        menu.selected = (menu.selected + rows - 1) % rows;
+    }
+    if actions.just_pressed(Action::Down) {
+        menu.selected = (menu.selected + 1) % rows;
+    }
+
+    if actions.just_pressed(Action::Back) {
+        transitions.send(Transition::To(GameState::Title));
+        return;
+    }
+    // `hover` has already selected the clicked row
+    let clicked = clicks.iter().any(|i| *i == Interaction::Clicked);
+    if !(actions.just_pressed(Action::Confirm) || clicked) {
+        return;
+    }
+
+    let mut cheats = config.cheats();
+    match Entry::ALL[menu.selected] {
+        Entry::Animatronic => {
+            menu.animatronic = (menu.animatronic + 1) % Animatronic::ALL.len();
+            menu.view = 0;
+        }
+        Entry::View => menu.view = (menu.view + 1) % views(menu.animatronic()).len(),
+        Entry::Jumpscare => jumpscares.send(Jumpscare(menu.animatronic().name().to_string())),
+        Entry::FastNights => cheats.fast_nights = !cheats.fast_nights,
+        Entry::InfinitePower => cheats.infinite_power = !cheats.infinite_power,
+        Entry::Radar => cheats.radar = !cheats.radar,
+    }
+    if cheats != config.cheats() {
+        config.set_cheats(cheats);
+    }
+}
+
+fn render(
+    mut commands: Commands,
+    asr: Res<AssetServer>,
+    menu: Res<ExtrasMenu>,
+    config: Res<Config>,
+    locale: Res<Locale>,
+    root: Query<(Entity, Option<&Children>, ChangeTrackers<Menu>)>,
+    mut shot: Query<&mut UiImage, With<Render>>,
+) {
+    let (root, children, tracker) = match root.get_single() {
+        Ok(root) => root,
+        Err(_) => return,
+    };
+    if !(tracker.is_added() || menu.is_changed() || config.is_changed() || locale.is_changed()) {
+        return;
+    }
+
+    for mut image in shot.iter_mut() {
+        image.0 = asr.load(views(menu.animatronic())[menu.view].path());
+    }
+
+    if let Some(children) = children {
+        for &child in children.iter() {
+            commands.entity(child).despawn_recursive();
+        }
+    }
+
+    commands.entity(root).with_children(|parent| {
+        for (i, entry) in Entry::ALL.iter().enumerate() {
+            let color = match i == menu.selected {
+                true => Color::WHITE,
+                false => Color::GRAY,
+            };
+
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        margin: Rect::all(Val::Px(8.0)),
+                        ..default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..default()
+                })
+                .insert(Row(i))
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            entry.label(&menu, &config, &locale),
+                            TextStyle {
+                                font: locale.font.clone(),
+                                font_size: 28.0,
+                                color,
+                            },
+                            default(),
+                        ),
+                        ..default()
+                    });
+                });
+        }
+    });
+}
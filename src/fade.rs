@@ -0,0 +1,371 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::system::EntityCommands,
+    prelude::*,
+    transform::TransformSystem,
+    ui::update::{ui_z_system, UI_Z_STEP},
+};
+use bevy_tweening::{
+    component_animator_system, Animator, EaseMethod, Lens, Tween, TweenCompleted, TweeningPlugin,
+    TweeningType,
+};
+
+/// Fades for screens and whatever is on them, built on `bevy_tweening`.
+///
+/// - `FadeScreen` fades the whole window through black, with a curtain over
+///   every screen.
+/// - `FadeCommands::fade` fades one entity's `UiColor`, `Sprite` or
+///   `TextureAtlasSprite` between transparent and opaque; `fade_all` fades a
+///   set of them, like a screen's tagged entities.
+///
+/// Either sends `Faded` with the id it was started with once it is done,
+/// hard cuts included. A set sends it once, when its last entity is done.
+pub struct FadePlugin;
+
+impl Plugin for FadePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(TweeningPlugin)
+            .add_event::<FadeScreen>()
+            .add_event::<Faded>()
+            .add_startup_system(setup)
+            .add_system(component_animator_system::<UiColor>)
+            .add_system(component_animator_system::<TextureAtlasSprite>)
+            .add_system(screen)
+            .add_system(hard)
+            .add_system(finish)
+            .add_system(sets.after(hard).after(finish))
+            .add_system_to_stage(
+                CoreStage::PostUpdate,
+                raise
+                    .after(ui_z_system)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+/// What a fade is for, sent back in `Faded`. Every fade anything waits on
+/// has its own, so two can't be mistaken for one another.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FadeId {
+    /// To black before a transition.
+    TransitionOut = 1,
+    /// Back from black after one.
+    TransitionIn,
+    /// The warning going away.
+    Warning,
+    /// A jumpscare covering the screen.
+    Scare,
+}
+
+impl FadeId {
+    const ALL: [FadeId; 4] = [
+        FadeId::TransitionOut,
+        FadeId::TransitionIn,
+        FadeId::Warning,
+        FadeId::Scare,
+    ];
+
+    /// The id carried through `bevy_tweening` as user data.
+    fn from_data(data: u64) -> Option<FadeId> {
+        FadeId::ALL.into_iter().find(|id| *id as u64 == data)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Cut {
+    /// Over the seconds.
+    Fade(f32),
+    /// At once.
+    Hard,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Fade {
+    /// From nothing to the screen, or an entity coming in.
+    In(Cut),
+    /// From the screen to black, or an entity going away.
+    Out(Cut),
+}
+
+impl Fade {
+    /// Opacity of the entity, or the screen, before and after.
+    fn alphas(self) -> (f32, f32) {
+        match self {
+            Fade::In(_) => (0.0, 1.0),
+            Fade::Out(_) => (1.0, 0.0),
+        }
+    }
+
+    fn cut(self) -> Cut {
+        match self {
+            Fade::In(cut) | Fade::Out(cut) => cut,
+        }
+    }
+
+    /// The same way, with another cut.
+    fn with(self, cut: Cut) -> Fade {
+        match self {
+            Fade::In(_) => Fade::In(cut),
+            Fade::Out(_) => Fade::Out(cut),
+        }
+    }
+}
+
+/// Fades the whole screen.
+pub struct FadeScreen {
+    pub fade: Fade,
+    pub id: FadeId,
+}
+
+/// A fade with `id` finished, or every fade of a `fade_all` set.
+pub struct Faded {
+    pub id: FadeId,
+}
+
+pub trait FadeCommands {
+    fn fade(&mut self, fade: Fade, id: FadeId) -> &mut Self;
+}
+
+impl FadeCommands for EntityCommands<'_, '_, '_> {
+    fn fade(&mut self, fade: Fade, id: FadeId) -> &mut Self {
+        let (start, end) = fade.alphas();
+        let seconds = match fade.cut() {
+            Cut::Fade(seconds) => seconds,
+            Cut::Hard => {
+                return self
+                    .remove::<Animator<UiColor>>()
+                    .remove::<Animator<Sprite>>()
+                    .remove::<Animator<TextureAtlasSprite>>()
+                    .insert(Hard {
+                        alpha: end,
+                        id: Some(id),
+                    });
+            }
+        };
+
+        // only the animator for the color the entity has runs
+        self.insert(Animator::new(tween::<UiColor>(start, end, seconds, id)))
+            .insert(Animator::new(tween::<Sprite>(start, end, seconds, id)))
+            .insert(Animator::new(tween::<TextureAtlasSprite>(
+                start, end, seconds, id,
+            )))
+    }
+}
+
+/// Fades every entity of a set, like everything tagged with a screen. The
+/// set sends one `Faded` once all of them are done or gone.
+pub fn fade_all(
+    commands: &mut Commands,
+    entities: impl IntoIterator<Item = Entity>,
+    fade: Fade,
+    id: FadeId,
+) {
+    let set = commands.spawn().id();
+    let mut members = Vec::new();
+    for entity in entities {
+        commands.entity(entity).insert(InSet(set)).fade(fade, id);
+        members.push(entity);
+    }
+    commands.entity(set).insert(FadeSet { members, id });
+}
+
+fn tween<T: Component>(start: f32, end: f32, seconds: f32, id: FadeId) -> Tween<T>
+where
+    Alpha: Lens<T>,
+{
+    Tween::new(
+        EaseMethod::Linear,
+        TweeningType::Once,
+        Duration::from_secs_f32(seconds),
+        Alpha { start, end },
+    )
+    .with_completed_event(true, id as u64)
+}
+
+/// Tweens the alpha of a color, leaving its tint alone.
+struct Alpha {
+    start: f32,
+    end: f32,
+}
+
+impl Alpha {
+    fn at(&self, ratio: f32) -> f32 {
+        self.start + (self.end - self.start) * ratio
+    }
+}
+
+impl Lens<UiColor> for Alpha {
+    fn lerp(&mut self, target: &mut UiColor, ratio: f32) {
+        target.0.set_a(self.at(ratio));
+    }
+}
+
+impl Lens<Sprite> for Alpha {
+    fn lerp(&mut self, target: &mut Sprite, ratio: f32) {
+        target.color.set_a(self.at(ratio));
+    }
+}
+
+impl Lens<TextureAtlasSprite> for Alpha {
+    fn lerp(&mut self, target: &mut TextureAtlasSprite, ratio: f32) {
+        target.color.set_a(self.at(ratio));
+    }
+}
+
+/// A hard cut waiting to be applied, and the id to send once it is.
+#[derive(Component)]
+struct Hard {
+    alpha: f32,
+    id: Option<FadeId>,
+}
+
+/// An entity still fading as part of the set `fade_all` spawned.
+#[derive(Component)]
+struct InSet(Entity);
+
+/// A set `fade_all` started, sending its `Faded` once no member is left.
+#[derive(Component)]
+struct FadeSet {
+    members: Vec<Entity>,
+    id: FadeId,
+}
+
+/// Black over every screen, opaque while the screen is faded out.
+#[derive(Component)]
+struct Curtain;
+
+fn setup(mut commands: Commands) {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(Curtain);
+}
+
+/// The curtain is opaque where the screen is faded out, so it runs the fade
+/// backwards.
+fn backwards(fade: Fade) -> Fade {
+    match fade {
+        Fade::In(cut) => Fade::Out(cut),
+        Fade::Out(cut) => Fade::In(cut),
+    }
+}
+
+fn screen(
+    mut commands: Commands,
+    mut fades: EventReader<FadeScreen>,
+    curtain: Query<(Entity, &UiColor), With<Curtain>>,
+) {
+    for FadeScreen { fade, id } in fades.iter() {
+        let (curtain, color) = match curtain.get_single() {
+            Ok(curtain) => curtain,
+            Err(_) => continue,
+        };
+
+        let fade = match fade.cut() {
+            // already there, like fading in a screen nothing faded out
+            Cut::Fade(_) if color.0.a() == backwards(*fade).alphas().1 => fade.with(Cut::Hard),
+            _ => *fade,
+        };
+        commands.entity(curtain).fade(backwards(fade), *id);
+    }
+}
+
+type Alphas<'a> = (
+    Entity,
+    &'a Hard,
+    Option<&'a mut UiColor>,
+    Option<&'a mut Sprite>,
+    Option<&'a mut TextureAtlasSprite>,
+);
+
+fn hard(
+    mut commands: Commands,
+    mut query: Query<Alphas>,
+    members: Query<&InSet>,
+    mut faded: EventWriter<Faded>,
+) {
+    for (entity, hard, ui, sprite, atlas) in query.iter_mut() {
+        if let Some(mut ui) = ui {
+            ui.0.set_a(hard.alpha);
+        }
+        if let Some(mut sprite) = sprite {
+            sprite.color.set_a(hard.alpha);
+        }
+        if let Some(mut atlas) = atlas {
+            atlas.color.set_a(hard.alpha);
+        }
+
+        commands.entity(entity).remove::<Hard>();
+        if let Some(id) = hard.id {
+            done(&mut commands, &members, &mut faded, entity, id);
+        }
+    }
+}
+
+/// Passes finished tweens on as `Faded`.
+fn finish(
+    mut commands: Commands,
+    mut completed: EventReader<TweenCompleted>,
+    mut faded: EventWriter<Faded>,
+    members: Query<&InSet>,
+) {
+    for event in completed.iter() {
+        if let Some(id) = FadeId::from_data(event.user_data) {
+            done(&mut commands, &members, &mut faded, event.entity, id);
+        }
+    }
+}
+
+/// Sends `Faded` for an entity done fading, or leaves it to its set.
+fn done(
+    commands: &mut Commands,
+    members: &Query<&InSet>,
+    faded: &mut EventWriter<Faded>,
+    entity: Entity,
+    id: FadeId,
+) {
+    match members.get(entity) {
+        Ok(_) => {
+            commands.entity(entity).remove::<InSet>();
+        }
+        Err(_) => faded.send(Faded { id }),
+    }
+}
+
+/// Sends a set's `Faded` once none of its members is still fading in it,
+/// despawned ones included.
+fn sets(
+    mut commands: Commands,
+    mut sets: Query<(Entity, &mut FadeSet)>,
+    members: Query<&InSet>,
+    mut faded: EventWriter<Faded>,
+) {
+    for (set, mut fading) in sets.iter_mut() {
+        fading
+            .members
+            .retain(|entity| matches!(members.get(*entity), Ok(InSet(s)) if *s == set));
+        if fading.members.is_empty() {
+            commands.entity(set).despawn();
+            faded.send(Faded { id: fading.id });
+        }
+    }
+}
+
+/// Lifts the curtain over every other UI node. Bevy stacks root nodes in the
+/// order it queries them, which can put a screen spawned later on top.
+fn raise(mut curtain: Query<&mut Transform, With<Curtain>>, nodes: Query<(), With<Node>>) {
+    let z = (nodes.iter().count() + 1) as f32 * UI_Z_STEP;
+    for mut transform in curtain.iter_mut() {
+        if transform.translation.z != z {
+            transform.translation.z = z;
+        }
+    }
+}
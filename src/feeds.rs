@@ -0,0 +1,401 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    animation::Animated,
+    assets::GameAssets,
+    input::Action,
+    locale::Locale,
+    map::{Animatronic, Camera, CameraButton, Monitor, MonitorButton, Positions},
+    mods::script::Actors,
+    office::OnOfficeScreen,
+    overlay::{Effect, Overlay, OverlayApp},
+    palette::Palette,
+    save::Config,
+    tuning::Tuning,
+    warp::{self, Pan, WarpMaterial},
+    GameState,
+};
+
+use Animatronic::{Bonnie, Chica, Freddy};
+
+/// The monitor and its camera feed: raises it and switches cameras with a
+/// blip, under the static of `add_overlay`. Picks the image for whoever is in the room and pans it back and
+/// forth. A pack animatronic with an image for the room shows over the
+/// originals.
+pub struct FeedPlugin;
+
+impl Plugin for FeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Positions>()
+            .init_resource::<Blip>()
+            .add_overlay::<OnOfficeScreen>(GameState::Frame1, &[Effect::Static, Effect::Blip])
+            .add_system_set(
+                SystemSet::on_enter(GameState::Frame1)
+                    .with_system(setup)
+                    .with_system(buttons),
+            )
+            .add_system_set(
+                SystemSet::on_update(GameState::Frame1)
+                    .with_system(switch)
+                    .with_system(show_map.after(switch))
+                    .with_system(overlays.after(switch))
+                    .with_system(resolve.after(switch))
+                    .with_system(auto_pan),
+            );
+    }
+}
+
+/// One image a camera can show. Rows with the same camera and occupants are
+/// variants of one another, picked at random by weight each time the feed
+/// changes.
+pub struct Shot {
+    pub camera: Camera,
+    /// Exactly who has to be in the room, Foxy aside.
+    pub occupants: &'static [Animatronic],
+    pub weight: u32,
+    pub image: GameAssets,
+}
+
+const fn shot(
+    camera: Camera,
+    occupants: &'static [Animatronic],
+    weight: u32,
+    image: GameAssets,
+) -> Shot {
+    Shot {
+        camera,
+        occupants,
+        weight,
+        image,
+    }
+}
+
+/// Every camera image. Cameras or occupants without a row show nothing, like
+/// the audio only kitchen.
+#[rustfmt::skip]
+pub const SHOTS: &[Shot] = &[
+    shot(Camera::Cam1A, &[Freddy, Bonnie, Chica], 1, GameAssets::CamShowStage),
+    shot(Camera::Cam1A, &[Freddy, Chica], 1, GameAssets::CamShowStageNoBonnie),
+    shot(Camera::Cam1A, &[Freddy, Bonnie], 1, GameAssets::CamShowStageNoChica),
+    shot(Camera::Cam1A, &[Freddy], 9, GameAssets::CamShowStageFreddy),
+    shot(Camera::Cam1A, &[Freddy], 1, GameAssets::CamShowStageFreddyStare),
+    shot(Camera::Cam1A, &[], 1, GameAssets::CamShowStageEmpty),
+    shot(Camera::Cam1B, &[], 1, GameAssets::CamDiningArea),
+    shot(Camera::Cam1B, &[Bonnie], 1, GameAssets::CamDiningAreaBonnie),
+    shot(Camera::Cam1B, &[Chica], 4, GameAssets::CamDiningAreaChica),
+    shot(Camera::Cam1B, &[Chica], 1, GameAssets::CamDiningAreaChicaClose),
+    shot(Camera::Cam1B, &[Freddy], 1, GameAssets::CamDiningAreaFreddy),
+    shot(Camera::Cam1C, &[], 1, GameAssets::CamPirateCove),
+    shot(Camera::Cam2A, &[], 1, GameAssets::CamWestHall),
+    shot(Camera::Cam2A, &[Bonnie], 1, GameAssets::CamWestHallBonnie),
+    shot(Camera::Cam2B, &[], 1, GameAssets::CamWestHallCorner),
+    shot(Camera::Cam2B, &[Bonnie], 1, GameAssets::CamWestHallCornerBonnie),
+    shot(Camera::Cam3, &[], 1, GameAssets::CamSupplyCloset),
+    shot(Camera::Cam3, &[Bonnie], 1, GameAssets::CamSupplyClosetBonnie),
+    shot(Camera::Cam4A, &[], 1, GameAssets::CamEastHall),
+    shot(Camera::Cam4A, &[Chica], 1, GameAssets::CamEastHallChica),
+    shot(Camera::Cam4A, &[Freddy], 1, GameAssets::CamEastHallFreddy),
+    shot(Camera::Cam4B, &[], 1, GameAssets::CamEastHallCorner),
+    shot(Camera::Cam4B, &[Chica], 1, GameAssets::CamEastHallCornerChica),
+    shot(Camera::Cam4B, &[Freddy], 1, GameAssets::CamEastHallCornerFreddy),
+    shot(Camera::Cam5, &[], 1, GameAssets::CamBackstage),
+    shot(Camera::Cam5, &[Bonnie], 4, GameAssets::CamBackstageBonnie),
+    shot(Camera::Cam5, &[Bonnie], 1, GameAssets::CamBackstageBonnieStare),
+    shot(Camera::Cam7, &[], 1, GameAssets::CamRestrooms),
+    shot(Camera::Cam7, &[Chica], 4, GameAssets::CamRestroomsChica),
+    shot(Camera::Cam7, &[Chica], 1, GameAssets::CamRestroomsChicaClose),
+    shot(Camera::Cam7, &[Freddy], 1, GameAssets::CamRestroomsFreddy),
+];
+
+/// Picks the image for `camera` given who is in its room. `roll` is given
+/// the total weight of the variants and returns a number below it.
+pub fn resolve_shot(
+    camera: Camera,
+    occupants: &[Animatronic],
+    roll: impl FnOnce(u32) -> u32,
+) -> Option<&'static Shot> {
+    let matches = |shot: &&Shot| {
+        shot.camera == camera
+            && shot.occupants.len() == occupants.len()
+            && shot.occupants.iter().all(|a| occupants.contains(a))
+    };
+
+    let total: u32 = SHOTS.iter().filter(matches).map(|s| s.weight).sum();
+    if total == 0 {
+        return None;
+    }
+
+    let mut roll = roll(total);
+    for shot in SHOTS.iter().filter(matches) {
+        if roll < shot.weight {
+            return Some(shot);
+        }
+        roll -= shot.weight;
+    }
+    None
+}
+
+/// The feed on the monitor.
+#[derive(Component)]
+pub struct Feed;
+
+/// Sweeps a `Pan` from end to end, resting at each end.
+#[derive(Component)]
+pub struct AutoPan {
+    /// 1 heading right, -1 heading left.
+    pub direction: f32,
+    pub wait: Timer,
+}
+
+impl AutoPan {
+    pub fn new(wait: f32) -> AutoPan {
+        AutoPan {
+            direction: 1.0,
+            wait: Timer::from_seconds(wait, false),
+        }
+    }
+}
+
+/// The camera buttons, shown while the monitor is up.
+#[derive(Component)]
+struct CameraMap;
+
+/// The blip after a camera switch, shown until the timer runs out.
+#[derive(Default)]
+struct Blip(Timer);
+
+fn setup(
+    mut commands: Commands,
+    tuning: Res<Tuning>,
+    mut monitor: ResMut<Monitor>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<WarpMaterial>>,
+) {
+    *monitor = Monitor::default();
+    let material = materials.add(WarpMaterial::new(default(), tuning.office.warp));
+    commands
+        // over the office, under the static
+        .spawn_bundle(warp::bundle(&mut meshes, material, 0.05))
+        .insert(Pan::new(0.0))
+        .insert(AutoPan::new(tuning.feed.wait))
+        .insert(Feed)
+        .insert(OnOfficeScreen);
+}
+
+/// The bar that raises the monitor along the bottom, and the map of camera
+/// buttons above its right end.
+fn buttons(mut commands: Commands, config: Res<Config>, locale: Res<Locale>) {
+    let idle = Palette::get(&config).idle;
+    let label = |text: String| TextBundle {
+        text: Text::with_section(
+            text,
+            TextStyle {
+                font: locale.font.clone(),
+                font_size: 16.0,
+                color: Color::BLACK,
+            },
+            default(),
+        ),
+        ..default()
+    };
+
+    commands
+        .spawn_bundle(ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(10.0),
+                    left: Val::Percent(30.0),
+                    ..default()
+                },
+                size: Size::new(Val::Percent(40.0), Val::Px(32.0)),
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: UiColor(idle),
+            ..default()
+        })
+        .insert(MonitorButton)
+        .insert(OnOfficeScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(label(locale.text("office.monitor").to_string()));
+        });
+
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    bottom: Val::Px(52.0),
+                    right: Val::Px(10.0),
+                    ..default()
+                },
+                size: Size::new(Val::Px(232.0), Val::Auto),
+                flex_wrap: FlexWrap::Wrap,
+                display: Display::None,
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(CameraMap)
+        .insert(OnOfficeScreen)
+        .with_children(|parent| {
+            for camera in Camera::ALL {
+                parent
+                    .spawn_bundle(ButtonBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(64.0), Val::Px(32.0)),
+                            margin: Rect::all(Val::Px(6.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        color: UiColor(idle),
+                        ..default()
+                    })
+                    .insert(CameraButton(camera))
+                    .with_children(|parent| {
+                        let text = locale.fill("office.camera", &[("camera", camera.label())]);
+                        parent.spawn_bundle(label(text));
+                    });
+            }
+        });
+}
+
+/// Raises and lowers the monitor and picks its camera, from the bindings or
+/// the buttons. A new camera comes in with a blip.
+fn switch(
+    actions: Res<Input<Action>>,
+    tuning: Res<Tuning>,
+    mut monitor: ResMut<Monitor>,
+    mut blip: ResMut<Blip>,
+    toggles: Query<&Interaction, (Changed<Interaction>, With<MonitorButton>)>,
+    cameras: Query<(&Interaction, &CameraButton), Changed<Interaction>>,
+) {
+    let toggled = toggles.iter().any(|i| *i == Interaction::Clicked);
+    if actions.just_pressed(Action::ToggleMonitor) || toggled {
+        monitor.up = !monitor.up;
+    }
+    if !monitor.up {
+        return;
+    }
+
+    let clicked = cameras
+        .iter()
+        .find(|(i, _)| **i == Interaction::Clicked)
+        .map(|(_, button)| button.0);
+    let picked = actions
+        .get_just_pressed()
+        .find_map(|a| a.camera())
+        .or(clicked);
+    if let Some(camera) = picked.filter(|c| *c != monitor.camera) {
+        monitor.camera = camera;
+        blip.0 = Timer::from_seconds(tuning.feed.blip, false);
+    }
+}
+
+/// The static shows over the feed while the monitor is up, and the blip for
+/// a moment after switching cameras.
+fn overlays(
+    time: Res<Time>,
+    monitor: Res<Monitor>,
+    mut blip: ResMut<Blip>,
+    mut query: Query<(&Overlay, &mut Animated, &mut Visibility)>,
+) {
+    blip.0.tick(time.delta());
+    for (overlay, mut animated, mut visibility) in query.iter_mut() {
+        let shown = match overlay.0 {
+            Effect::Blip => monitor.up && !blip.0.finished(),
+            _ => monitor.up,
+        };
+        animated.show(shown, &mut visibility);
+    }
+}
+
+fn show_map(monitor: Res<Monitor>, mut map: Query<&mut Style, With<CameraMap>>) {
+    if !monitor.is_changed() {
+        return;
+    }
+    for mut style in map.iter_mut() {
+        style.display = match monitor.up {
+            true => Display::Flex,
+            false => Display::None,
+        };
+    }
+}
+
+/// What the feed shows depends on: the monitor, who is in the room and a
+/// pack animatronic's image for it.
+type View = (bool, Camera, Vec<Animatronic>, Option<String>);
+
+#[allow(clippy::too_many_arguments)]
+fn resolve(
+    asr: Res<AssetServer>,
+    monitor: Res<Monitor>,
+    positions: Res<Positions>,
+    actors: Res<Actors>,
+    mut last: Local<Option<View>>,
+    mut materials: ResMut<Assets<WarpMaterial>>,
+    mut query: Query<(&Handle<WarpMaterial>, &mut Visibility), With<Feed>>,
+    added: Query<(), Added<Feed>>,
+) {
+    let room = monitor.camera.room();
+    let occupants: Vec<Animatronic> = positions
+        .occupants(room)
+        .filter(|a| *a != Animatronic::Foxy)
+        .collect();
+    let modded = actors
+        .actors
+        .iter()
+        .filter(|a| a.room == room)
+        .find_map(|a| a.cameras.get(&room))
+        .cloned();
+
+    // actors change every night step, only roll a new variant on a new view
+    let view = (monitor.up, monitor.camera, occupants, modded);
+    if last.as_ref() == Some(&view) && added.is_empty() {
+        return;
+    }
+    let (_, _, occupants, modded) = last.insert(view);
+
+    let image = match modded {
+        Some(image) => Some(image.as_str()),
+        None => {
+            let mut rng = rand::thread_rng();
+            resolve_shot(monitor.camera, occupants, |total| rng.gen_range(0..total))
+                .map(|shot| shot.image.file())
+        }
+    };
+
+    for (handle, mut visibility) in query.iter_mut() {
+        visibility.is_visible = monitor.up && image.is_some();
+
+        if let (Some(image), Some(material)) = (image, materials.get_mut(handle)) {
+            material.texture = asr.load(image);
+        }
+    }
+}
+
+fn auto_pan(time: Res<Time>, tuning: Res<Tuning>, mut query: Query<(&mut Pan, &mut AutoPan)>) {
+    for (mut pan, mut auto) in query.iter_mut() {
+        if !auto.wait.finished() {
+            auto.wait.tick(time.delta());
+            continue;
+        }
+
+        let direction = auto.direction;
+        pan.scroll(direction * tuning.feed.pan * time.delta_seconds());
+
+        let end = match direction > 0.0 {
+            true => pan.at >= 1.0,
+            false => pan.at <= 0.0,
+        };
+        if end {
+            auto.direction = -direction;
+            auto.wait.reset();
+        }
+    }
+}
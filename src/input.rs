@@ -0,0 +1,183 @@
+use bevy::{input::InputSystem, prelude::*};
+use serde::{Deserialize, Serialize};
+
+use crate::{map::Camera, save::Config};
+
+/// Turns keys, mouse buttons and gamepad buttons into `Input<Action>`, using
+/// the bindings stored in the config. Screens read actions, never raw keys.
+pub struct ActionPlugin;
+
+impl Plugin for ActionPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Input<Action>>()
+            .init_resource::<Rebind>()
+            .add_system_to_stage(CoreStage::PreUpdate, rebind.after(InputSystem))
+            .add_system_to_stage(
+                CoreStage::PreUpdate,
+                actions.after(InputSystem).after(rebind),
+            );
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Action {
+    Confirm,
+    Back,
+    Up,
+    Down,
+    LeftDoor,
+    RightDoor,
+    LeftLight,
+    RightLight,
+    ToggleMonitor,
+    Cam1A,
+    Cam1B,
+    Cam1C,
+    Cam2A,
+    Cam2B,
+    Cam3,
+    Cam4A,
+    Cam4B,
+    Cam5,
+    Cam6,
+    Cam7,
+    Fullscreen,
+}
+
+impl Action {
+    /// The camera a `CamN` action switches to.
+    pub fn camera(self) -> Option<Camera> {
+        match self {
+            Action::Cam1A => Some(Camera::Cam1A),
+            Action::Cam1B => Some(Camera::Cam1B),
+            Action::Cam1C => Some(Camera::Cam1C),
+            Action::Cam2A => Some(Camera::Cam2A),
+            Action::Cam2B => Some(Camera::Cam2B),
+            Action::Cam3 => Some(Camera::Cam3),
+            Action::Cam4A => Some(Camera::Cam4A),
+            Action::Cam4B => Some(Camera::Cam4B),
+            Action::Cam5 => Some(Camera::Cam5),
+            Action::Cam6 => Some(Camera::Cam6),
+            Action::Cam7 => Some(Camera::Cam7),
+            _ => None,
+        }
+    }
+}
+
+/// Everything that triggers one action. Saved as `[[bindings]]` tables in
+/// the config.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Binding {
+    pub action: Action,
+    #[serde(default)]
+    pub keys: Vec<KeyCode>,
+    #[serde(default)]
+    pub mouse: Vec<MouseButton>,
+    #[serde(default)]
+    pub buttons: Vec<GamepadButtonType>,
+}
+
+impl Binding {
+    fn new(action: Action, keys: &[KeyCode], buttons: &[GamepadButtonType]) -> Binding {
+        Binding {
+            action,
+            keys: keys.to_vec(),
+            mouse: Vec::new(),
+            buttons: buttons.to_vec(),
+        }
+    }
+}
+
+pub fn default_bindings() -> Vec<Binding> {
+    use GamepadButtonType as Pad;
+    use KeyCode as Key;
+
+    // clicks aren't bound, the menus' buttons take them where they land
+    vec![
+        Binding::new(
+            Action::Confirm,
+            &[Key::Return, Key::NumpadEnter],
+            &[Pad::South],
+        ),
+        Binding::new(Action::Back, &[Key::Escape], &[Pad::East]),
+        Binding::new(Action::Up, &[Key::Up, Key::W], &[Pad::DPadUp]),
+        Binding::new(Action::Down, &[Key::Down, Key::S], &[Pad::DPadDown]),
+        Binding::new(Action::LeftDoor, &[Key::A], &[Pad::LeftTrigger]),
+        Binding::new(Action::RightDoor, &[Key::D], &[Pad::RightTrigger]),
+        Binding::new(Action::LeftLight, &[Key::Q], &[Pad::LeftTrigger2]),
+        Binding::new(Action::RightLight, &[Key::E], &[Pad::RightTrigger2]),
+        Binding::new(Action::ToggleMonitor, &[Key::Space], &[Pad::North]),
+        Binding::new(Action::Cam1A, &[Key::Key1], &[]),
+        Binding::new(Action::Cam1B, &[Key::Key2], &[]),
+        Binding::new(Action::Cam1C, &[Key::Key3], &[]),
+        Binding::new(Action::Cam2A, &[Key::Key4], &[]),
+        Binding::new(Action::Cam2B, &[Key::Key5], &[]),
+        Binding::new(Action::Cam3, &[Key::Key6], &[]),
+        Binding::new(Action::Cam4A, &[Key::Key7], &[]),
+        Binding::new(Action::Cam4B, &[Key::Key8], &[]),
+        Binding::new(Action::Cam5, &[Key::Key9], &[]),
+        Binding::new(Action::Cam6, &[Key::Key0], &[]),
+        Binding::new(Action::Cam7, &[Key::Minus], &[]),
+        Binding::new(Action::Fullscreen, &[Key::F11], &[Pad::Select]),
+    ]
+}
+
+/// Set to an action to bind the next key or gamepad button pressed to it,
+/// replacing its current keys or buttons. Mouse bindings are left alone.
+#[derive(Default)]
+pub struct Rebind(pub Option<Action>);
+
+fn rebind(
+    mut rebind: ResMut<Rebind>,
+    mut config: ResMut<Config>,
+    keys: Res<Input<KeyCode>>,
+    buttons: Res<Input<GamepadButton>>,
+) {
+    let action = match rebind.0 {
+        Some(action) => action,
+        None => return,
+    };
+
+    if let Some(&key) = keys.get_just_pressed().next() {
+        config.rebind(action, |binding| binding.keys = vec![key]);
+        rebind.0 = None;
+    } else if let Some(&GamepadButton(_, button)) = buttons.get_just_pressed().next() {
+        config.rebind(action, |binding| binding.buttons = vec![button]);
+        rebind.0 = None;
+    }
+}
+
+fn actions(
+    config: Res<Config>,
+    rebind: Res<Rebind>,
+    keys: Res<Input<KeyCode>>,
+    mouse: Res<Input<MouseButton>>,
+    gamepads: Res<Gamepads>,
+    buttons: Res<Input<GamepadButton>>,
+    mut actions: ResMut<Input<Action>>,
+) {
+    actions.clear();
+
+    // Whatever is pressed while rebinding is meant for the new binding.
+    if rebind.0.is_some() {
+        return;
+    }
+
+    for binding in config.bindings() {
+        let pressed = binding.keys.iter().any(|&key| keys.pressed(key))
+            || binding.mouse.iter().any(|&button| mouse.pressed(button))
+            || gamepads.iter().any(|&gamepad| {
+                binding
+                    .buttons
+                    .iter()
+                    .any(|&button| buttons.pressed(GamepadButton(gamepad, button)))
+            });
+
+        if pressed && !actions.pressed(binding.action) {
+            actions.press(binding.action);
+        } else if !pressed && actions.pressed(binding.action) {
+            actions.release(binding.action);
+        }
+    }
+}
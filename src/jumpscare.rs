@@ -0,0 +1,163 @@
+use bevy::{prelude::*, render::camera::Camera2d};
+
+use crate::{
+    animation::AnimationPlugin,
+    audio::{Channel, Mixer, PlaySound},
+    despawn_screen,
+    doors::Jumpscare,
+    fade::{Cut, Fade, FadeId, FadeScreen},
+    overlay::{self, Effect},
+    pause::Pausable,
+    save::Config,
+    transition::Transition,
+    tuning::Tuning,
+    GameState,
+};
+
+/// Ends the night when an animatronic gets in: the overlay's static covers
+/// the screen under the scream, then goes back to the title. There is no jumpscare art
+/// yet, the static is all there is to see. With `soften_jumpscares` the
+/// screen fades to black instead and the scream plays quieter.
+///
+/// The extras menu replays them by sending `Jumpscare`, which plays the same
+/// and then cuts back to the menu.
+pub struct JumpscarePlugin;
+
+/// States a jumpscare can play in.
+const SCREENS: [GameState; 2] = [GameState::Frame1, GameState::Extras];
+
+impl Plugin for JumpscarePlugin {
+    fn build(&self, app: &mut App) {
+        // the night animates its own, for the static over the feed
+        app.add_plugin(AnimationPlugin(GameState::Extras));
+        for screen in SCREENS {
+            app.add_system_set(
+                SystemSet::on_update(screen.clone())
+                    .with_system(start)
+                    .with_system(scare.after(start)),
+            )
+            .add_system_set(
+                SystemSet::on_exit(screen)
+                    .with_system(reset)
+                    .with_system(despawn_screen::<OnScare>),
+            );
+        }
+    }
+}
+
+/// A jumpscare playing, until the timer runs out.
+#[derive(Component)]
+struct Scare(Timer);
+
+/// Everything a jumpscare spawns.
+#[derive(Component)]
+struct OnScare;
+
+#[allow(clippy::too_many_arguments)]
+fn start(
+    mut commands: Commands,
+    asr: Res<AssetServer>,
+    config: Res<Config>,
+    tuning: Res<Tuning>,
+    mut mixer: ResMut<Mixer>,
+    mut pausable: ResMut<Pausable>,
+    mut jumpscares: EventReader<Jumpscare>,
+    mut sounds: EventWriter<PlaySound>,
+    mut screen: EventWriter<FadeScreen>,
+    mut textures: ResMut<Assets<TextureAtlas>>,
+    scares: Query<&Scare>,
+    cameras: Query<(), With<Camera2d>>,
+) {
+    let jumpscare = match jumpscares.iter().last() {
+        Some(jumpscare) => jumpscare,
+        None => return,
+    };
+    if !scares.is_empty() {
+        return;
+    }
+    info!("{} got in", jumpscare.0);
+
+    let soft = config.accessibility().soften_jumpscares;
+    let limits = &tuning.accessibility;
+    pausable.0 = false;
+    mixer.set_gain(
+        Channel::Jumpscare,
+        match soft {
+            true => limits.scare_volume,
+            false => 1.0,
+        },
+    );
+    sounds.send(PlaySound {
+        channel: Channel::Jumpscare,
+        sound: load!(asr, Scream),
+        looped: false,
+    });
+
+    commands
+        .spawn()
+        .insert(Scare(Timer::from_seconds(limits.scare_fade, false)))
+        .insert(OnScare);
+    if soft {
+        screen.send(FadeScreen {
+            fade: Fade::Out(Cut::Fade(limits.scare_fade)),
+            id: FadeId::Scare,
+        });
+        return;
+    }
+
+    overlay::spawn_effect(
+        &mut commands,
+        Effect::Static,
+        &asr,
+        &mut textures,
+        &tuning,
+        &config,
+    )
+    // over the office and the feed
+    .insert(Transform::from_xyz(0.0, 0.0, 100.0))
+    .insert(OnScare);
+    // the static only shows through a 2D camera, which menus don't have
+    if cameras.is_empty() {
+        commands
+            .spawn_bundle(OrthographicCameraBundle::new_2d())
+            .insert(OnScare);
+    }
+}
+
+/// There is no game over screen yet, so the scare ends on the title.
+#[allow(clippy::too_many_arguments)]
+fn scare(
+    mut commands: Commands,
+    time: Res<Time>,
+    state: Res<State<GameState>>,
+    mut transitions: EventWriter<Transition>,
+    mut screen: EventWriter<FadeScreen>,
+    mut pausable: ResMut<Pausable>,
+    mut query: Query<&mut Scare>,
+    spawned: Query<Entity, With<OnScare>>,
+) {
+    for mut scare in query.iter_mut() {
+        if !scare.0.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        match state.current() {
+            GameState::Frame1 => transitions.send(Transition::To(GameState::Title)),
+            // a replay
+            _ => {
+                screen.send(FadeScreen {
+                    fade: Fade::In(Cut::Hard),
+                    id: FadeId::Scare,
+                });
+                for entity in spawned.iter() {
+                    commands.entity(entity).despawn_recursive();
+                }
+                pausable.0 = true;
+            }
+        }
+    }
+}
+
+fn reset(mut pausable: ResMut<Pausable>) {
+    pausable.0 = true;
+}
@@ -0,0 +1,380 @@
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    ecs::system::EntityCommands,
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{
+    counter::{Align, Counter},
+    from_ct,
+    locale::{Locale, Localized},
+    save::Config,
+};
+
+pub struct LayoutPlugin;
+
+impl Plugin for LayoutPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Layout>()
+            .init_asset_loader::<LayoutLoader>()
+            .add_system(reload_layouts);
+    }
+}
+
+/// A screen described by a `.layout.toml` file.
+///
+/// Elements are spawned in file order, so later UI elements draw over
+/// earlier ones. Sprites and atlases are ordered by the `z` of their frame.
+#[derive(Deserialize, TypeUuid)]
+#[uuid = "4faa45bf-a181-4c86-aa40-23d911115936"]
+pub struct Layout {
+    #[serde(default, rename = "element")]
+    pub elements: Vec<Element>,
+}
+
+#[derive(Deserialize)]
+pub struct Element {
+    pub kind: Kind,
+    pub image: String,
+    /// Placement for `image` and `button` elements, in UI pixels.
+    pub ui: Option<Ui>,
+    /// Placement for `sprite`, `atlas` and `counter` elements, in Clickteam
+    /// frame coordinates. A counter's hot spot is where its number starts or
+    /// ends, by its `align`.
+    pub frame: Option<Frame>,
+    pub grid: Option<Grid>,
+    /// Which end of a `counter` sits on its hot spot.
+    #[serde(default)]
+    pub align: Align,
+    pub color: Option<[f32; 4]>,
+    #[serde(default)]
+    pub visible: Condition,
+    /// Screen specific markers, handed to [`Screen::tag`].
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default, rename = "child")]
+    pub children: Vec<Element>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum Kind {
+    Image,
+    Button,
+    Sprite,
+    Atlas,
+    /// A `Counter<u32>` drawn from an atlas of digits, bound to a value by
+    /// the screen's tag.
+    Counter,
+}
+
+#[derive(Deserialize)]
+pub struct Ui {
+    pub left: f32,
+    pub bottom: f32,
+    pub width: f32,
+    pub height: f32,
+    #[serde(default = "absolute")]
+    pub absolute: bool,
+}
+
+fn absolute() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+pub struct Frame {
+    pub x: f32,
+    pub y: f32,
+    pub w: f32,
+    pub h: f32,
+    #[serde(default)]
+    pub ax: f32,
+    #[serde(default)]
+    pub ay: f32,
+    #[serde(default = "z")]
+    pub z: f32,
+}
+
+fn z() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize)]
+pub struct Grid {
+    pub tile: [f32; 2],
+    pub columns: usize,
+    pub rows: usize,
+    #[serde(default)]
+    pub index: usize,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    #[default]
+    Always,
+    Never,
+    FirstNight,
+    Continued,
+    BeatGame,
+    BeatSix,
+    BeatSeven,
+}
+
+impl Condition {
+    pub fn check(&self, config: &Config) -> bool {
+        match self {
+            Condition::Always => true,
+            Condition::Never => false,
+            Condition::FirstNight => config.level() == 1,
+            Condition::Continued => config.level() > 1,
+            Condition::BeatGame => config.beatgame(),
+            Condition::BeatSix => config.beat_six(),
+            Condition::BeatSeven => config.beat_seven(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LayoutLoader;
+
+impl AssetLoader for LayoutLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let layout: Layout = toml::from_slice(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(layout));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["layout.toml"]
+    }
+}
+
+/// Marker for the screen a layout belongs to.
+///
+/// Every top level element gets `Self::default()` so the screen's usual
+/// `despawn_unload::<Self>` cleans it up.
+pub trait Screen: Component + Default {
+    /// Attach screen specific components for a tag listed in the layout.
+    fn tag(_entity: &mut EntityCommands, _tag: &str) {}
+}
+
+/// Spawn alongside a screen marker to have the layout spawned once loaded.
+#[derive(Component)]
+pub struct LayoutHandle(pub Handle<Layout>);
+
+#[derive(Component)]
+pub struct LayoutSpawned;
+
+/// Screens of type `T` whose layout hasn't been spawned yet.
+type Unspawned<T> = (With<T>, Without<LayoutSpawned>);
+
+/// A top level entity spawned from the layout owned by the given entity.
+#[derive(Component)]
+pub struct LayoutElement(pub Entity);
+
+pub fn spawn_layout<T: Screen>(
+    mut commands: Commands,
+    asr: Res<AssetServer>,
+    config: Res<Config>,
+    locale: Res<Locale>,
+    layouts: Res<Assets<Layout>>,
+    mut atlases: ResMut<Assets<TextureAtlas>>,
+    query: Query<(Entity, &LayoutHandle), Unspawned<T>>,
+) {
+    for (entity, handle) in query.iter() {
+        let layout = match layouts.get(&handle.0) {
+            Some(layout) => layout,
+            None => continue,
+        };
+
+        for element in layout.elements.iter() {
+            let mut e = commands.spawn();
+            e.insert(T::default()).insert(LayoutElement(entity));
+            spawn_element::<T>(&mut e, element, &asr, &config, &locale, &mut atlases);
+        }
+
+        commands.entity(entity).insert(LayoutSpawned);
+    }
+}
+
+/// Respawn a layout in place when its file changes on disk.
+fn reload_layouts(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Layout>>,
+    handles: Query<(Entity, &LayoutHandle), With<LayoutSpawned>>,
+    elements: Query<(Entity, &LayoutElement)>,
+) {
+    for event in events.iter() {
+        let modified = match event {
+            AssetEvent::Modified { handle } => handle,
+            _ => continue,
+        };
+
+        for (owner, handle) in handles.iter() {
+            if handle.0 != *modified {
+                continue;
+            }
+
+            for (entity, element) in elements.iter() {
+                if element.0 == owner {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+
+            commands.entity(owner).remove::<LayoutSpawned>();
+        }
+    }
+}
+
+fn spawn_element<T: Screen>(
+    e: &mut EntityCommands,
+    element: &Element,
+    asr: &AssetServer,
+    config: &Config,
+    locale: &Locale,
+    atlases: &mut Assets<TextureAtlas>,
+) {
+    let visibility = Visibility {
+        is_visible: element.visible.check(config),
+    };
+    let color = element
+        .color
+        .map(|[r, g, b, a]| Color::rgba(r, g, b, a))
+        .unwrap_or(Color::WHITE);
+    let image = locale.image(&element.image);
+
+    match (element.kind, &element.ui, &element.frame, &element.grid) {
+        (Kind::Image, Some(ui), _, _) => {
+            e.insert_bundle(ImageBundle {
+                image: UiImage(asr.load(image)),
+                color: UiColor(color),
+                style: style(ui),
+                visibility,
+                ..default()
+            });
+        }
+        (Kind::Button, Some(ui), _, _) => {
+            e.insert_bundle(ButtonBundle {
+                image: UiImage(asr.load(image)),
+                color: UiColor(color),
+                style: style(ui),
+                visibility,
+                ..default()
+            });
+        }
+        (Kind::Sprite, _, frame, _) => {
+            e.insert_bundle(SpriteBundle {
+                texture: asr.load(image),
+                sprite: Sprite { color, ..default() },
+                transform: frame.as_ref().map(transform).unwrap_or_default(),
+                visibility,
+                ..default()
+            });
+        }
+        (Kind::Atlas, _, Some(frame), Some(grid)) => {
+            let sheet = asr.load(image);
+            let texture_atlas = TextureAtlas::from_grid(
+                sheet,
+                Vec2::new(grid.tile[0], grid.tile[1]),
+                grid.columns,
+                grid.rows,
+            );
+
+            e.insert_bundle(SpriteSheetBundle {
+                texture_atlas: atlases.add(texture_atlas),
+                sprite: TextureAtlasSprite {
+                    index: grid.index,
+                    color,
+                    ..default()
+                },
+                transform: transform(frame),
+                visibility,
+                ..default()
+            });
+        }
+        (Kind::Counter, _, Some(frame), Some(grid)) => {
+            let sheet = asr.load(image);
+            let texture_atlas = TextureAtlas::from_grid(
+                sheet,
+                Vec2::new(grid.tile[0], grid.tile[1]),
+                grid.columns,
+                grid.rows,
+            );
+
+            // from the frame's centre to the end on the hot spot
+            let mut transform = transform(frame);
+            transform.translation.x += match element.align {
+                Align::Left => -frame.w / 2.0,
+                Align::Right => frame.w / 2.0,
+            };
+            e.insert_bundle(TransformBundle::from_transform(transform))
+                .insert(visibility)
+                .insert(
+                    Counter::new(atlases.add(texture_atlas), 0u32, grid.tile[0])
+                        .align(element.align),
+                );
+        }
+        (kind, ..) => {
+            warn!(
+                "layout: {:?} '{}' is missing its placement",
+                kind, element.image
+            );
+            return;
+        }
+    }
+
+    // atlases keep their texture in the atlas, so only follow the language
+    // they were spawned with
+    if !matches!(element.kind, Kind::Atlas | Kind::Counter) {
+        e.insert(Localized(element.image.clone()));
+    }
+
+    for tag in element.tags.iter() {
+        T::tag(e, tag);
+    }
+
+    if !element.children.is_empty() {
+        e.with_children(|p| {
+            for child in element.children.iter() {
+                spawn_element::<T>(&mut p.spawn(), child, asr, config, locale, atlases);
+            }
+        });
+    }
+}
+
+fn style(ui: &Ui) -> Style {
+    Style {
+        position_type: match ui.absolute {
+            true => PositionType::Absolute,
+            false => PositionType::Relative,
+        },
+        position: Rect {
+            left: Val::Px(ui.left),
+            bottom: Val::Px(ui.bottom),
+            ..default()
+        },
+        size: Size {
+            width: Val::Px(ui.width),
+            height: Val::Px(ui.height),
+        },
+        ..default()
+    }
+}
+
+fn transform(frame: &Frame) -> Transform {
+    Transform {
+        translation: from_ct!(frame.x, frame.y, frame.w, frame.h, frame.ax, frame.ay, frame.z),
+        ..default()
+    }
+}
@@ -0,0 +1,200 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{assets::GameAssets, save::Config};
+
+pub struct LocalePlugin;
+
+impl Plugin for LocalePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Strings>()
+            .init_asset_loader::<StringsLoader>()
+            .init_resource::<Locale>()
+            // the config is inserted at startup
+            .add_startup_system_to_stage(StartupStage::PostStartup, switch)
+            .add_system(switch)
+            .add_system(apply.after(switch))
+            .add_system(localize.after(apply));
+    }
+}
+
+/// Every language the game ships, by the code stored in the config.
+pub const LANGUAGES: &[(&str, GameAssets)] =
+    &[("en", GameAssets::LocaleEn), ("es", GameAssets::LocaleEs)];
+
+/// A `locales/<code>.locale.toml` file: the strings drawn as text and
+/// replacements for images with text baked in.
+#[derive(Deserialize, TypeUuid, Clone, Default)]
+#[uuid = "0b2a4b51-7d55-4a4e-9c1d-2f3e8a6c1d90"]
+#[serde(default)]
+pub struct Strings {
+    /// The language's own name for itself.
+    pub name: String,
+    /// Font for the text, for scripts `fonts/ui.ttf` doesn't cover.
+    pub font: Option<String>,
+    pub strings: HashMap<String, String>,
+    /// Replacement image path by original image path.
+    pub images: HashMap<String, String>,
+    /// Short caption by sound path, shown when the sound plays.
+    pub captions: HashMap<String, String>,
+    /// `.subtitles.toml` file by sound path, for the phone calls.
+    pub subtitles: HashMap<String, String>,
+}
+
+/// The language in use. Systems drawing text read it through `text` and
+/// redraw when it changes.
+#[derive(Default)]
+pub struct Locale {
+    pub language: String,
+    pub strings: Strings,
+    pub font: Handle<Font>,
+}
+
+impl Locale {
+    /// The string for `key`, or the key itself if the language misses it.
+    pub fn text<'a>(&'a self, key: &'a str) -> &'a str {
+        match self.strings.strings.get(key) {
+            Some(text) => text,
+            None => key,
+        }
+    }
+
+    /// `text` with each `{name}` replaced by its value.
+    pub fn fill(&self, key: &str, values: &[(&str, &str)]) -> String {
+        let mut text = self.text(key).to_string();
+        for (name, value) in values {
+            text = text.replace(&format!("{{{}}}", name), value);
+        }
+        text
+    }
+
+    /// The image to load in place of `path`.
+    pub fn image<'a>(&'a self, path: &'a str) -> &'a str {
+        match self.strings.images.get(path) {
+            Some(path) => path,
+            None => path,
+        }
+    }
+}
+
+/// An image that has a localized version, by its original path. It is
+/// swapped whenever the language changes.
+#[derive(Component)]
+pub struct Localized(pub String);
+
+#[derive(Default)]
+pub struct StringsLoader;
+
+impl AssetLoader for StringsLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let strings: Strings = toml::from_slice(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(strings));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["locale.toml"]
+    }
+}
+
+pub struct StringsHandle(Handle<Strings>);
+
+/// Loads the strings for the language in the config when it changes.
+fn switch(
+    mut commands: Commands,
+    asr: Res<AssetServer>,
+    config: Option<Res<Config>>,
+    handle: Option<Res<StringsHandle>>,
+    mut locale: ResMut<Locale>,
+) {
+    let config = match config {
+        Some(config) => config,
+        None => return,
+    };
+    if handle.is_some() && locale.language == config.language() {
+        return;
+    }
+
+    let file = match LANGUAGES
+        .iter()
+        .find(|(code, _)| *code == config.language())
+    {
+        Some((_, file)) => file,
+        None => {
+            warn!("locale: no language '{}'", config.language());
+            &LANGUAGES[0].1
+        }
+    };
+    locale.language = config.language().to_string();
+    commands.insert_resource(StringsHandle(asr.load(file.path())));
+}
+
+/// Copies the strings into `Locale` once loaded, when switching to a
+/// language loaded before and when the file is edited.
+fn apply(
+    asr: Res<AssetServer>,
+    mut events: EventReader<AssetEvent<Strings>>,
+    handle: Option<Res<StringsHandle>>,
+    assets: Res<Assets<Strings>>,
+    mut locale: ResMut<Locale>,
+) {
+    let handle = match handle {
+        Some(handle) => handle,
+        None => return,
+    };
+    let loaded = events.iter().any(|event| match event {
+        AssetEvent::Created { handle: h } | AssetEvent::Modified { handle: h } => *h == handle.0,
+        _ => false,
+    });
+    if !(loaded || handle.is_changed()) {
+        return;
+    }
+
+    if let Some(strings) = assets.get(&handle.0) {
+        info!("locale: {}", strings.name);
+        locale.strings = strings.clone();
+        locale.font = match &strings.font {
+            Some(font) => asr.load(font.as_str()),
+            None => load!(asr, UiFont),
+        };
+    }
+}
+
+type Images<'a> = (
+    &'a Localized,
+    Option<&'a mut UiImage>,
+    Option<&'a mut Handle<Image>>,
+);
+
+fn localize(
+    asr: Res<AssetServer>,
+    locale: Res<Locale>,
+    mut images: Query<Images>,
+) {
+    if !locale.is_changed() {
+        return;
+    }
+
+    for (localized, ui, sprite) in images.iter_mut() {
+        let image = asr.load(locale.image(&localized.0));
+        if let Some(mut ui) = ui {
+            ui.0 = image.clone();
+        }
+        if let Some(mut sprite) = sprite {
+            *sprite = image;
+        }
+    }
+}
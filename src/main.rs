@@ -1,31 +1,59 @@
-#![feature(core_intrinsics)]
 #![windows_subsystem = "windows"]
 
-use std::intrinsics::unlikely;
-
 use assets::GameAssetsIo;
 use bevy::{
     app::AppExit,
-    asset::AssetPlugin,
+    asset::{create_platform_default_asset_io, AssetIo, AssetPlugin},
     prelude::*,
     window::{WindowMode, WindowResizeConstraints},
     DefaultPlugins,
 };
-use bevy_embasset::EmbassetPlugin;
+use bevy_embasset::EmbassetIo;
 use bevy_kira_audio::AudioPlugin;
+use input::Action;
+use mods::ModLayer;
 
 #[macro_export]
 macro_rules! load {
     ($asr:expr, $name:ident) => {
-        $asr.load(crate::assets::GameAssets::$name)
+        $asr.load($crate::assets::GameAssets::$name)
     };
 }
 
+mod achievements;
+mod ai;
+mod animation;
 mod assets;
+mod audio;
+mod captions;
+mod check;
+mod clickteam;
 mod counter;
+mod days;
+#[cfg(any(debug_assertions, feature = "dev"))]
+mod debug;
+mod doors;
+mod extras;
+mod fade;
+mod feeds;
+mod input;
+mod jumpscare;
+mod layout;
+mod locale;
+mod map;
+mod mods;
+mod night;
+mod office;
+mod overlay;
+mod pack;
+mod palette;
+mod pause;
 mod save;
 mod title;
+mod transition;
+mod tuning;
 mod warning;
+mod warp;
 
 #[derive(Clone, PartialEq, Eq, Debug, Hash)]
 pub enum GameState {
@@ -46,11 +74,65 @@ pub enum GameState {
     CreepyStart,
     CreepyEnd,
     EndOfDemo,
+    Pause,
+    Trophies,
+    Extras,
+}
+
+impl GameState {
+    pub const ALL: [GameState; 20] = [
+        GameState::Frame17,
+        GameState::Title,
+        GameState::WhatDay,
+        GameState::Frame1,
+        GameState::Died,
+        GameState::Freddy,
+        GameState::NextDay,
+        GameState::Wait,
+        GameState::GameOver,
+        GameState::TheEnd,
+        GameState::Ad,
+        GameState::TheEnd2,
+        GameState::Customize,
+        GameState::TheEnd3,
+        GameState::CreepyStart,
+        GameState::CreepyEnd,
+        GameState::EndOfDemo,
+        GameState::Pause,
+        GameState::Trophies,
+        GameState::Extras,
+    ];
 }
 
 fn main() {
+    let mut args = std::env::args().skip(1).map(std::path::PathBuf::from);
+    let mut data = None;
+    match args.next() {
+        Some(cmd) if cmd.as_os_str() == "check-assets" => {
+            console();
+            exit(check::run(args.next()))
+        }
+        Some(cmd) if cmd.as_os_str() == "pack-sheets" => {
+            console();
+            exit(pack::run(args.next(), args.next()))
+        }
+        Some(cmd) if cmd.as_os_str() == "check-mods" => {
+            console();
+            exit(mods::run(args.next()))
+        }
+        Some(cmd) if cmd.as_os_str() == "--data" => data = args.next(),
+        _ => {}
+    }
+
     let mut app = App::new();
 
+    // Read straight from `assets/` and reload anything edited while running.
+    #[cfg(feature = "dev")]
+    app.insert_resource(bevy::asset::AssetServerSettings {
+        asset_folder: "assets".to_string(),
+        watch_for_changes: true,
+    });
+
     app.insert_resource(WindowDescriptor {
         width: 1280.0,
         height: 720.0,
@@ -68,17 +150,61 @@ fn main() {
     })
     .insert_resource(ClearColor(Color::rgb(0.0, 0.0, 0.0)))
     .add_plugins_with(DefaultPlugins, |group| {
-        group.add_before::<AssetPlugin, _>(EmbassetPlugin::new(|io| {
-            io.add_handler(GameAssetsIo::new().into());
-        }))
+        group.add_before::<AssetPlugin, _>(mods::LayerPlugin::new(
+            ModLayer::default(),
+            move |app| -> Box<dyn AssetIo> {
+                if cfg!(feature = "dev") {
+                    return create_platform_default_asset_io(app);
+                }
+
+                if let Some(data) = &data {
+                    match clickteam::PackAssetIo::open(app, data) {
+                        Ok(io) => return Box::new(io),
+                        Err(e) => error!(
+                            "can't read game data {}, using the embedded assets: {}",
+                            data.display(),
+                            e
+                        ),
+                    }
+                }
+
+                let mut io = EmbassetIo::new();
+                io.add_handler(GameAssetsIo::new().into());
+                Box::new(io)
+            },
+        ))
     })
     .add_plugin(AudioPlugin)
+    .add_plugin(audio::MixerPlugin)
     .add_plugin(save::ConfigPlugin)
+    .add_plugin(input::ActionPlugin)
+    .add_plugin(layout::LayoutPlugin)
+    .add_plugin(locale::LocalePlugin)
+    .add_plugin(captions::CaptionPlugin)
+    .add_plugin(counter::CounterPlugin)
+    .add_plugin(tuning::TuningPlugin)
+    .add_plugin(night::NightPlugin)
+    .add_plugin(mods::ModPlugin)
+    .add_plugin(warp::WarpPlugin)
     .add_system(escape)
     .add_system(view)
     .add_state(GameState::Frame17)
+    .add_plugin(fade::FadePlugin)
+    .add_plugin(transition::TransitionPlugin)
     .add_plugin(warning::WarningPlugin)
-    .add_plugin(title::TitlePlugin);
+    .add_plugin(title::TitlePlugin)
+    .add_plugin(office::OfficePlugin)
+    .add_plugin(feeds::FeedPlugin)
+    .add_plugin(doors::DoorPlugin)
+    .add_plugin(jumpscare::JumpscarePlugin)
+    .add_plugin(palette::PalettePlugin)
+    .add_plugin(pause::PausePlugin)
+    .add_plugin(days::DayPlugin)
+    .add_plugin(achievements::AchievementPlugin)
+    .add_plugin(extras::ExtrasPlugin);
+
+    #[cfg(any(debug_assertions, feature = "dev"))]
+    app.add_plugin(debug::DebugPlugin);
 
     #[cfg(target_os = "windows")]
     app.insert_resource(Backends::DX11);
@@ -86,8 +212,32 @@ fn main() {
     app.run();
 }
 
-fn view(keys: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
-    if unlikely(keys.just_pressed(KeyCode::F11)) {
+/// The subcommands report on stdout, but a `windows` subsystem binary starts
+/// without a console; borrow the one it was run from.
+#[cfg(target_os = "windows")]
+fn console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process: u32) -> i32;
+    }
+
+    // SAFETY: takes no pointers; failing just leaves the output unseen
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn console() {}
+
+fn exit(ok: bool) -> ! {
+    std::process::exit(if ok { 0 } else { 1 })
+}
+
+fn view(actions: Res<Input<Action>>, mut windows: ResMut<Windows>) {
+    if actions.just_pressed(Action::Fullscreen) {
         let window = windows.primary_mut();
         let new = match window.mode() {
             WindowMode::Windowed => WindowMode::SizedFullscreen,
@@ -99,17 +249,26 @@ fn view(keys: Res<Input<KeyCode>>, mut windows: ResMut<Windows>) {
 }
 
 fn escape(
-    keys: Res<Input<KeyCode>>,
+    actions: Res<Input<Action>>,
     mut exit: EventWriter<AppExit>,
     game_state: Res<State<GameState>>,
 ) {
-    if keys.just_pressed(KeyCode::Escape)
+    // nights pause instead, menus go back, or ignore it where the original
+    // wouldn't pause
+    if actions.just_pressed(Action::Back)
         && ![
+            GameState::Frame1,
+            GameState::Died,
+            GameState::Freddy,
+            GameState::NextDay,
+            GameState::Pause,
             GameState::CreepyEnd,
             GameState::CreepyStart,
             GameState::Ad,
             GameState::WhatDay,
             GameState::Wait,
+            GameState::Trophies,
+            GameState::Extras,
         ]
         .contains(game_state.current())
     {
@@ -0,0 +1,217 @@
+/// Places on the pizzeria map an animatronic can be in.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Room {
+    ShowStage,
+    DiningArea,
+    PirateCove,
+    WestHall,
+    WestHallCorner,
+    SupplyCloset,
+    EastHall,
+    EastHallCorner,
+    Backstage,
+    Kitchen,
+    Restrooms,
+    LeftDoor,
+    RightDoor,
+    Office,
+}
+
+impl Room {
+    pub const ALL: [Room; 14] = [
+        Room::ShowStage,
+        Room::DiningArea,
+        Room::PirateCove,
+        Room::WestHall,
+        Room::WestHallCorner,
+        Room::SupplyCloset,
+        Room::EastHall,
+        Room::EastHallCorner,
+        Room::Backstage,
+        Room::Kitchen,
+        Room::Restrooms,
+        Room::LeftDoor,
+        Room::RightDoor,
+        Room::Office,
+    ];
+
+    /// Names mod packs and scripts refer to rooms by, as in `mod.toml`.
+    pub fn name(self) -> &'static str {
+        match self {
+            Room::ShowStage => "show_stage",
+            Room::DiningArea => "dining_area",
+            Room::PirateCove => "pirate_cove",
+            Room::WestHall => "west_hall",
+            Room::WestHallCorner => "west_hall_corner",
+            Room::SupplyCloset => "supply_closet",
+            Room::EastHall => "east_hall",
+            Room::EastHallCorner => "east_hall_corner",
+            Room::Backstage => "backstage",
+            Room::Kitchen => "kitchen",
+            Room::Restrooms => "restrooms",
+            Room::LeftDoor => "left_door",
+            Room::RightDoor => "right_door",
+            Room::Office => "office",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Room> {
+        Room::ALL.into_iter().find(|r| r.name() == name)
+    }
+
+    pub fn camera(&self) -> Option<Camera> {
+        Camera::ALL.iter().copied().find(|c| c.room() == *self)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Camera {
+    Cam1A,
+    Cam1B,
+    Cam1C,
+    Cam2A,
+    Cam2B,
+    Cam3,
+    Cam4A,
+    Cam4B,
+    Cam5,
+    Cam6,
+    Cam7,
+}
+
+impl Camera {
+    pub const ALL: [Camera; 11] = [
+        Camera::Cam1A,
+        Camera::Cam1B,
+        Camera::Cam1C,
+        Camera::Cam2A,
+        Camera::Cam2B,
+        Camera::Cam3,
+        Camera::Cam4A,
+        Camera::Cam4B,
+        Camera::Cam5,
+        Camera::Cam6,
+        Camera::Cam7,
+    ];
+
+    pub fn room(&self) -> Room {
+        match self {
+            Camera::Cam1A => Room::ShowStage,
+            Camera::Cam1B => Room::DiningArea,
+            Camera::Cam1C => Room::PirateCove,
+            Camera::Cam2A => Room::WestHall,
+            Camera::Cam2B => Room::WestHallCorner,
+            Camera::Cam3 => Room::SupplyCloset,
+            Camera::Cam4A => Room::EastHall,
+            Camera::Cam4B => Room::EastHallCorner,
+            Camera::Cam5 => Room::Backstage,
+            Camera::Cam6 => Room::Kitchen,
+            Camera::Cam7 => Room::Restrooms,
+        }
+    }
+
+    /// The number on the map, like `1A`.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Camera::Cam1A => "1A",
+            Camera::Cam1B => "1B",
+            Camera::Cam1C => "1C",
+            Camera::Cam2A => "2A",
+            Camera::Cam2B => "2B",
+            Camera::Cam3 => "3",
+            Camera::Cam4A => "4A",
+            Camera::Cam4B => "4B",
+            Camera::Cam5 => "5",
+            Camera::Cam6 => "6",
+            Camera::Cam7 => "7",
+        }
+    }
+}
+
+/// Clickable camera on the monitor's map.
+#[derive(bevy::prelude::Component)]
+pub struct CameraButton(pub Camera);
+
+/// Clickable bar that raises and lowers the monitor.
+#[derive(bevy::prelude::Component)]
+pub struct MonitorButton;
+
+/// The camera monitor. The last camera stays selected while it's down.
+pub struct Monitor {
+    pub up: bool,
+    pub camera: Camera,
+}
+
+impl Default for Monitor {
+    fn default() -> Self {
+        Monitor {
+            up: false,
+            camera: Camera::Cam1A,
+        }
+    }
+}
+
+impl Monitor {
+    pub fn watching(&self, room: Room) -> bool {
+        self.up && self.camera.room() == room
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Animatronic {
+    Freddy,
+    Bonnie,
+    Chica,
+    Foxy,
+}
+
+impl Animatronic {
+    /// Names mod packs refer to the originals by.
+    pub fn name(self) -> &'static str {
+        match self {
+            Animatronic::Freddy => "freddy",
+            Animatronic::Bonnie => "bonnie",
+            Animatronic::Chica => "chica",
+            Animatronic::Foxy => "foxy",
+        }
+    }
+
+    pub const ALL: [Animatronic; 4] = [
+        Animatronic::Freddy,
+        Animatronic::Bonnie,
+        Animatronic::Chica,
+        Animatronic::Foxy,
+    ];
+}
+
+/// Where every animatronic is. The AI moves them, the cameras and the office
+/// read it.
+pub struct Positions(pub [Room; 4]);
+
+impl Default for Positions {
+    fn default() -> Self {
+        Positions([
+            Room::ShowStage,
+            Room::ShowStage,
+            Room::ShowStage,
+            Room::PirateCove,
+        ])
+    }
+}
+
+impl Positions {
+    pub fn room(&self, animatronic: Animatronic) -> Room {
+        self.0[animatronic as usize]
+    }
+
+    pub fn set(&mut self, animatronic: Animatronic, room: Room) {
+        self.0[animatronic as usize] = room;
+    }
+
+    pub fn occupants(&self, room: Room) -> impl Iterator<Item = Animatronic> + '_ {
+        Animatronic::ALL
+            .into_iter()
+            .filter(move |a| self.room(*a) == room)
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use bevy::{
+    asset::{AssetIo, AssetIoError},
+    prelude::*,
+    tasks::IoTaskPool,
+    utils::BoxedFuture,
+};
+
+/// The `assets/` folder of the selected mod pack, shared between the
+/// `LayeredAssetIo` and the mod selector so picking a pack takes effect for
+/// everything loaded afterwards.
+#[derive(Clone, Default)]
+pub struct ModLayer(Arc<RwLock<Option<PathBuf>>>);
+
+impl ModLayer {
+    pub fn set(&self, dir: Option<PathBuf>) {
+        *self.0.write().unwrap() = dir;
+    }
+
+    fn file(&self, path: &Path) -> Option<PathBuf> {
+        let dir = self.0.read().unwrap();
+        let file = dir.as_ref()?.join(path);
+        file.is_file().then_some(file)
+    }
+}
+
+/// Makes the `AssetIo` the game would use without mods.
+type BaseIo = Box<dyn Fn(&mut App) -> Box<dyn AssetIo> + Send + Sync>;
+
+/// Builds the asset server on top of whichever `AssetIo` the game would use
+/// otherwise, with the selected mod pack's files taking precedence.
+pub struct LayerPlugin {
+    layer: ModLayer,
+    base: BaseIo,
+}
+
+impl LayerPlugin {
+    pub fn new(
+        layer: ModLayer,
+        base: impl Fn(&mut App) -> Box<dyn AssetIo> + Send + Sync + 'static,
+    ) -> LayerPlugin {
+        LayerPlugin {
+            layer,
+            base: Box::new(base),
+        }
+    }
+}
+
+impl Plugin for LayerPlugin {
+    fn build(&self, app: &mut App) {
+        let base = (self.base)(app);
+        let task_pool = app.world.resource::<IoTaskPool>().0.clone();
+
+        app.insert_resource(self.layer.clone())
+            .insert_resource(AssetServer::new(
+                LayeredAssetIo {
+                    layer: self.layer.clone(),
+                    base,
+                },
+                task_pool,
+            ));
+    }
+}
+
+pub struct LayeredAssetIo {
+    layer: ModLayer,
+    base: Box<dyn AssetIo>,
+}
+
+impl AssetIo for LayeredAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            match self.layer.file(path) {
+                Some(file) => std::fs::read(&file).map_err(|e| match e.kind() {
+                    std::io::ErrorKind::NotFound => AssetIoError::NotFound(file),
+                    _ => AssetIoError::Io(e),
+                }),
+                None => self.base.load_path(path).await,
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        self.base.read_directory(path)
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        self.base.is_directory(path)
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        self.base.watch_path_for_changes(path)
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.base.watch_for_changes()
+    }
+}
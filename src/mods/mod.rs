@@ -0,0 +1,508 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    assets::GameAssets,
+    locale::Locale,
+    map::{Animatronic, Room},
+    save::Config,
+    title::OnTitleScreen,
+    transition::Transition,
+    GameState,
+};
+
+pub mod io;
+pub mod script;
+
+pub use io::{LayerPlugin, ModLayer};
+
+/// Fan made nights and animatronics from `mods/<pack>/mod.toml`, with the
+/// pack's `assets/` folder shadowing the game's own files.
+pub struct ModPlugin;
+
+impl Plugin for ModPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(script::ScriptPlugin)
+            .add_startup_system_to_stage(StartupStage::PostStartup, discover)
+            .add_system_set(SystemSet::on_enter(GameState::Title).with_system(selector))
+            .add_system_set(SystemSet::on_update(GameState::Title).with_system(select));
+    }
+}
+
+/// `mod.toml`, the manifest at the root of a pack.
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    pub name: String,
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub author: String,
+    #[serde(default, rename = "night")]
+    pub nights: Vec<Night>,
+    #[serde(default, rename = "animatronic")]
+    pub animatronics: Vec<AnimatronicDef>,
+}
+
+impl Manifest {
+    /// Its name, with the version and author if it has them.
+    pub fn describe(&self) -> String {
+        let mut out = self.name.clone();
+        if !self.version.is_empty() {
+            out = format!("{} {}", out, self.version);
+        }
+        if !self.author.is_empty() {
+            out = format!("{} by {}", out, self.author);
+        }
+        out
+    }
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Night {
+    pub name: String,
+    /// AI levels from 12 AM to 5 AM, 0 to 20, by animatronic name. The
+    /// originals are `freddy`, `bonnie`, `chica` and `foxy`.
+    #[serde(default)]
+    pub ai: HashMap<String, Vec<u8>>,
+    /// Multiplier on the usual power drain.
+    #[serde(default = "one")]
+    pub drain: f32,
+}
+
+fn one() -> f32 {
+    1.0
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct AnimatronicDef {
+    pub name: String,
+    pub start: Room,
+    /// Where it can go from each room. Getting into the office starts its
+    /// attack.
+    #[serde(rename = "move")]
+    pub moves: Vec<Move>,
+    pub jumpscare: Option<Jumpscare>,
+    #[serde(default)]
+    pub sounds: Sounds,
+    /// Image shown on the camera of a room while it's there, by room name,
+    /// relative to the asset root.
+    #[serde(default)]
+    pub cameras: HashMap<String, String>,
+    /// Rhai script driving it, relative to the pack. See `script`.
+    pub script: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Move {
+    pub from: Room,
+    pub to: Vec<Room>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct Jumpscare {
+    /// Sprite sheet, relative to the asset root.
+    pub sheet: String,
+    pub tile: [u32; 2],
+    pub columns: usize,
+    pub rows: usize,
+    /// Seconds per frame.
+    pub frame: f32,
+}
+
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub struct Sounds {
+    pub jumpscare: Option<String>,
+    /// Played from the room it moves into.
+    pub step: Option<String>,
+}
+
+pub struct ModPack {
+    pub dir: PathBuf,
+    pub manifest: Manifest,
+}
+
+impl ModPack {
+    /// Reads and validates the pack in `dir`, returning every problem found.
+    pub fn load(dir: &Path) -> Result<ModPack, Vec<String>> {
+        let text = std::fs::read_to_string(dir.join("mod.toml"))
+            .map_err(|e| vec![format!("can't read mod.toml: {}", e)])?;
+        let manifest: Manifest =
+            toml::from_str(&text).map_err(|e| vec![format!("mod.toml: {}", e)])?;
+
+        let pack = ModPack {
+            dir: dir.to_path_buf(),
+            manifest,
+        };
+        let problems = pack.validate();
+        match problems.is_empty() {
+            true => Ok(pack),
+            false => Err(problems),
+        }
+    }
+
+    fn assets(&self) -> PathBuf {
+        self.dir.join("assets")
+    }
+
+    /// Whether `path` is in this pack or the game.
+    fn exists(&self, path: &str) -> bool {
+        self.assets().join(path).is_file() || GameAssets::ALL.iter().any(|(_, p)| *p == path)
+    }
+
+    fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let manifest = &self.manifest;
+
+        if manifest.name.trim().is_empty() {
+            problems.push("name is empty".to_string());
+        }
+
+        let mut names: HashSet<&str> = Animatronic::ALL.iter().map(|a| a.name()).collect();
+        let mut referenced = HashSet::new();
+        for animatronic in manifest.animatronics.iter() {
+            let name = animatronic.name.as_str();
+            if !names.insert(name) {
+                problems.push(format!("animatronic '{}' is defined twice", name));
+            }
+            problems.extend(
+                check_animatronic(self, animatronic, &mut referenced)
+                    .into_iter()
+                    .map(|p| format!("animatronic '{}': {}", name, p)),
+            );
+        }
+
+        for night in manifest.nights.iter() {
+            for (name, levels) in night.ai.iter() {
+                if !names.contains(name.as_str()) {
+                    problems.push(format!(
+                        "night '{}': no animatronic called '{}'",
+                        night.name, name
+                    ));
+                }
+                if levels.len() != 6 {
+                    problems.push(format!(
+                        "night '{}': '{}' needs 6 AI levels, 12 AM to 5 AM, not {}",
+                        night.name,
+                        name,
+                        levels.len()
+                    ));
+                }
+                if let Some(level) = levels.iter().find(|l| **l > 20) {
+                    problems.push(format!(
+                        "night '{}': '{}' has AI level {}, the most is 20",
+                        night.name, name, level
+                    ));
+                }
+            }
+            if night.drain.is_nan() || night.drain < 0.0 {
+                problems.push(format!(
+                    "night '{}': drain can't be {}",
+                    night.name, night.drain
+                ));
+            }
+        }
+
+        // anything else in assets/ is likely a misnamed replacement
+        for file in files(&self.assets()) {
+            let path = file.strip_prefix(self.assets()).unwrap();
+            let path = path.to_string_lossy().replace('\\', "/");
+            let replaces = GameAssets::ALL.iter().any(|(_, p)| *p == path);
+            if !replaces && !referenced.contains(&path) {
+                problems.push(format!(
+                    "assets/{} doesn't replace a game file and isn't used by the manifest",
+                    path
+                ));
+            }
+        }
+
+        problems
+    }
+}
+
+fn check_animatronic(
+    pack: &ModPack,
+    animatronic: &AnimatronicDef,
+    referenced: &mut HashSet<String>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    let graph: HashMap<Room, &[Room]> = animatronic
+        .moves
+        .iter()
+        .map(|m| (m.from, m.to.as_slice()))
+        .collect();
+    for m in animatronic.moves.iter() {
+        for to in m.to.iter() {
+            if *to != Room::Office && !graph.contains_key(to) {
+                problems.push(format!("can move to {:?} but has no moves from there", to));
+            }
+        }
+    }
+
+    // walk the graph from the start, it has to be able to attack
+    let mut seen = HashSet::new();
+    let mut next = vec![animatronic.start];
+    while let Some(room) = next.pop() {
+        if seen.insert(room) {
+            next.extend(graph.get(&room).copied().unwrap_or_default());
+        }
+    }
+    if !seen.contains(&Room::Office) {
+        problems.push(format!(
+            "can never reach the office from {:?}",
+            animatronic.start
+        ));
+    }
+
+    if let Some(jumpscare) = &animatronic.jumpscare {
+        referenced.insert(jumpscare.sheet.clone());
+        if !pack.exists(&jumpscare.sheet) {
+            problems.push(format!("jumpscare sheet {} is missing", jumpscare.sheet));
+        }
+        if jumpscare.columns == 0 || jumpscare.rows == 0 {
+            problems.push("jumpscare sheet has no frames".to_string());
+        }
+        if jumpscare.tile.contains(&0) {
+            problems.push("jumpscare tile has to be at least 1 pixel".to_string());
+        }
+        if jumpscare.frame.is_nan() || jumpscare.frame <= 0.0 {
+            problems.push("jumpscare frame time has to be above 0".to_string());
+        }
+    }
+
+    for sound in [&animatronic.sounds.jumpscare, &animatronic.sounds.step]
+        .into_iter()
+        .flatten()
+    {
+        referenced.insert(sound.clone());
+        if !pack.exists(sound) {
+            problems.push(format!("sound {} is missing", sound));
+        }
+    }
+
+    for (room, image) in animatronic.cameras.iter() {
+        referenced.insert(image.clone());
+        match Room::parse(room) {
+            Some(r) if r.camera().is_none() => {
+                problems.push(format!("{} has no camera to show {} on", room, image))
+            }
+            Some(_) => {}
+            None => problems.push(format!("no room called '{}'", room)),
+        }
+        if !pack.exists(image) {
+            problems.push(format!("camera image {} is missing", image));
+        }
+    }
+
+    if let Some(file) = &animatronic.script {
+        if let Err(e) = script::engine().compile_file(pack.dir.join(file)) {
+            problems.push(format!("script {}: {}", file, e));
+        }
+    }
+
+    problems
+}
+
+fn files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        match path.is_dir() {
+            true => files.extend(self::files(&path)),
+            false => files.push(path),
+        }
+    }
+    files
+}
+
+pub fn default_root() -> PathBuf {
+    match std::env::var("CARGO_MANIFEST_DIR") {
+        Ok(dir) => PathBuf::from(dir).join("mods"),
+        Err(_) => PathBuf::from("mods"),
+    }
+}
+
+/// Every pack under `root`, by folder name, sorted.
+fn scan(root: &Path) -> Vec<(String, Result<ModPack, Vec<String>>)> {
+    let mut packs: Vec<_> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .map(|dir| {
+            let name = dir.file_name().unwrap().to_string_lossy().into_owned();
+            (name, ModPack::load(&dir))
+        })
+        .collect();
+    packs.sort_by(|a, b| a.0.cmp(&b.0));
+    packs
+}
+
+/// `frnaf check-mods [root]`
+pub fn run(root: Option<PathBuf>) -> bool {
+    let root = root.unwrap_or_else(default_root);
+    println!("checking mod packs in {}", root.display());
+
+    let packs = scan(&root);
+    let mut ok = true;
+    for (name, pack) in packs.iter() {
+        match pack {
+            Ok(pack) => println!("  ok       {} ({})", name, pack.manifest.describe()),
+            Err(problems) => {
+                ok = false;
+                for problem in problems {
+                    println!("  PROBLEM  {}: {}", name, problem);
+                }
+            }
+        }
+    }
+
+    if packs.is_empty() {
+        println!("no mod packs found");
+    }
+    ok
+}
+
+/// Packs that passed validation. Broken ones are logged and left out.
+#[derive(Default)]
+pub struct Mods {
+    pub packs: Vec<ModPack>,
+    /// Index into `packs`, `None` for the unmodded game.
+    pub selected: Option<usize>,
+}
+
+impl Mods {
+    pub fn current(&self) -> Option<&ModPack> {
+        self.packs.get(self.selected?)
+    }
+
+    /// The selected pack's night for `level`, from 1.
+    pub fn night(&self, level: u8) -> Option<&Night> {
+        self.current()?
+            .manifest
+            .nights
+            .get(level.saturating_sub(1) as usize)
+    }
+
+    /// Multiplier on the power drain for `level`, 1 without a pack.
+    pub fn drain(&self, level: u8) -> f32 {
+        self.night(level).map_or(1.0, |night| night.drain)
+    }
+}
+
+fn discover(mut commands: Commands, config: Res<Config>, layer: Option<Res<ModLayer>>) {
+    let mut mods = Mods::default();
+    for (name, pack) in scan(&default_root()) {
+        match pack {
+            Ok(pack) => {
+                info!("mod {}: {}", name, pack.manifest.describe());
+                mods.packs.push(pack);
+            }
+            Err(problems) => {
+                for problem in problems {
+                    warn!("mod {}: {}", name, problem);
+                }
+            }
+        }
+    }
+
+    mods.selected = config
+        .mod_pack()
+        .and_then(|name| mods.packs.iter().position(|p| p.dir.ends_with(name)));
+    if let Some(layer) = layer {
+        layer.set(mods.current().map(ModPack::assets));
+    }
+    info!("{} mod packs", mods.packs.len());
+
+    commands.insert_resource(mods);
+}
+
+#[derive(Component)]
+struct Selector;
+
+fn label(mods: &Mods, locale: &Locale) -> String {
+    match mods.current() {
+        Some(pack) => locale.fill("mods.selected", &[("name", &pack.manifest.name)]),
+        None => locale.text("mods.none").to_string(),
+    }
+}
+
+fn selector(mut commands: Commands, locale: Res<Locale>, mods: Res<Mods>) {
+    if mods.packs.is_empty() {
+        return;
+    }
+
+    commands
+        .spawn_bundle(ButtonBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: Rect {
+                    right: Val::Px(16.0),
+                    bottom: Val::Px(16.0),
+                    ..default()
+                },
+                ..default()
+            },
+            color: UiColor(Color::NONE),
+            ..default()
+        })
+        .insert(Selector)
+        .insert(OnTitleScreen)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle {
+                text: Text::with_section(
+                    label(&mods, &locale),
+                    TextStyle {
+                        font: locale.font.clone(),
+                        font_size: 20.0,
+                        color: Color::GRAY,
+                    },
+                    default(),
+                ),
+                ..default()
+            });
+        });
+}
+
+/// Cycles through the packs, then back to the unmodded game, and restarts
+/// the title so it is loaded again with the new files.
+fn select(
+    mut mods: ResMut<Mods>,
+    mut config: ResMut<Config>,
+    layer: Option<Res<ModLayer>>,
+    mut transitions: EventWriter<Transition>,
+    buttons: Query<&Interaction, (Changed<Interaction>, With<Selector>)>,
+) {
+    for interaction in buttons.iter() {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+
+        mods.selected = match mods.selected {
+            None => Some(0),
+            Some(i) if i + 1 < mods.packs.len() => Some(i + 1),
+            Some(_) => None,
+        };
+        let name = mods
+            .current()
+            .and_then(|p| p.dir.file_name())
+            .map(|n| n.to_string_lossy().into_owned());
+        config.set_mod_pack(name);
+        if let Some(layer) = &layer {
+            layer.set(mods.current().map(ModPack::assets));
+        }
+        transitions.send(Transition::Restart);
+    }
+}
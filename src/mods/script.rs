@@ -0,0 +1,647 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rhai::{module_resolvers::DummyModuleResolver, CallFnOptions, Dynamic, Engine, Scope, AST};
+
+use super::{AnimatronicDef, Mods};
+use crate::{
+    ai::{self, Original, Watch, ORIGINALS},
+    audio::spatial::PlayAt,
+    doors::{Door, Doors, Jumpscare, Side},
+    map::{Animatronic, Monitor, Positions, Room},
+    night::{Clock, NightRng, NightStage, Power, Tick, RATE},
+    save::Config,
+    tuning::Tuning,
+    GameState,
+};
+
+/// Ticks between a pack animatronic's movement opportunities, 5 seconds,
+/// about what Bonnie and Chica have.
+pub const OPPORTUNITY: u64 = 5 * RATE;
+
+/// Moves the animatronics inside the night step: the originals by their AI
+/// and a pack's by the dice or their Rhai scripts.
+///
+/// A script defines either callback, both optional:
+///
+/// - `on_move()` at every movement opportunity. Without it the animatronic
+///   moves like the originals: if `this.roll()` passes, to a random room out
+///   of `this.moves()`. So do animatronics with no script at all.
+/// - `on_camera(room)` when the monitor switches to `room`, or to `""` when it
+///   comes down.
+///
+/// `this` is the night as seen by the animatronic; every function the API
+/// has is below in `engine`. Scripts can't reach the file system, load
+/// modules or run for longer than `MAX_OPERATIONS`.
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(engine())
+            .init_resource::<Actors>()
+            .add_system_set(SystemSet::on_enter(GameState::Frame1).with_system(load))
+            .add_system_set(SystemSet::on_update(GameState::Frame1).with_system(place))
+            .add_system_set_to_stage(
+                NightStage,
+                SystemSet::on_update(GameState::Frame1).with_system(run.after(Tick)),
+            );
+    }
+}
+
+const MAX_OPERATIONS: u64 = 50_000;
+
+/// The sandboxed engine scripts are checked and run with.
+pub fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .disable_symbol("eval")
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_call_levels(32)
+        .set_max_expr_depths(64, 32)
+        .set_max_string_size(1024)
+        .set_max_array_size(256)
+        .set_max_map_size(256)
+        .on_print(|text| info!("script: {}", text))
+        .on_debug(|text, _, pos| debug!("script {}: {}", pos, text));
+
+    engine
+        .register_type_with_name::<NightApi>("Night")
+        .register_fn("tick", |n: &mut NightApi| n.tick as i64)
+        .register_fn("hour", |n: &mut NightApi| n.hour as i64)
+        .register_fn("power", |n: &mut NightApi| n.power as f64)
+        .register_fn("ai", |n: &mut NightApi| n.ai as i64)
+        .register_fn("room", |n: &mut NightApi| n.room.name().to_string())
+        .register_fn("moves", |n: &mut NightApi| {
+            n.moves
+                .iter()
+                .map(|r| Dynamic::from(r.name().to_string()))
+                .collect::<rhai::Array>()
+        })
+        .register_fn("room_of", NightApi::room_of)
+        .register_fn("camera", |n: &mut NightApi| {
+            n.camera.map(Room::name).unwrap_or_default().to_string()
+        })
+        .register_fn("watched", |n: &mut NightApi| n.camera == Some(n.room))
+        .register_fn("door_closed", |n: &mut NightApi, side: &str| {
+            n.door(side).map(|d| d.closed)
+        })
+        .register_fn("light_on", |n: &mut NightApi, side: &str| {
+            n.door(side).map(|d| d.light)
+        })
+        .register_fn("random", |n: &mut NightApi, below: i64| match below > 0 {
+            true => n.rng.gen_range(0..below),
+            false => 0,
+        })
+        .register_fn("roll", NightApi::roll)
+        .register_fn("move_to", NightApi::move_to)
+        .register_fn("play", |n: &mut NightApi, sound: &str| {
+            n.sounds.push(sound.to_string())
+        })
+        .register_fn("drain", |n: &mut NightApi, percent: f64| {
+            n.drain += percent.max(0.0) as f32
+        });
+
+    engine
+}
+
+/// What a script sees of the night, and what it asked for.
+#[derive(Clone)]
+pub struct NightApi {
+    tick: u64,
+    hour: u8,
+    power: f32,
+    ai: u8,
+    room: Room,
+    moves: Vec<Room>,
+    positions: [Room; 4],
+    /// The room on the monitor, if it's up.
+    camera: Option<Room>,
+    doors: [Door; 2],
+    /// Lent from `NightRng` for the call.
+    rng: StdRng,
+
+    move_to: Option<Room>,
+    sounds: Vec<String>,
+    drain: f32,
+    /// The last d20 rolled against `ai`.
+    rolled: Option<u8>,
+}
+
+impl NightApi {
+    /// Rolls a d20, passing at or under the AI level like the originals.
+    fn roll(&mut self) -> bool {
+        let value = self.rng.gen_range(1..=20);
+        self.rolled = Some(value);
+        value <= self.ai
+    }
+
+    fn door(&self, side: &str) -> Result<Door, Box<rhai::EvalAltResult>> {
+        match side {
+            "left" => Ok(self.doors[Side::Left as usize]),
+            "right" => Ok(self.doors[Side::Right as usize]),
+            _ => Err(format!("no door on the '{}' side", side).into()),
+        }
+    }
+
+    fn room_of(&mut self, name: &str) -> Result<String, Box<rhai::EvalAltResult>> {
+        match Animatronic::ALL.into_iter().find(|a| a.name() == name) {
+            Some(a) => Ok(self.positions[a as usize].name().to_string()),
+            None => Err(format!("no animatronic called '{}'", name).into()),
+        }
+    }
+
+    fn move_to(&mut self, room: &str) -> Result<(), Box<rhai::EvalAltResult>> {
+        match Room::parse(room) {
+            Some(room) if self.moves.contains(&room) => {
+                self.move_to = Some(room);
+                Ok(())
+            }
+            Some(_) => Err(format!("can't move from {} to {}", self.room.name(), room).into()),
+            None => Err(format!("no room called '{}'", room).into()),
+        }
+    }
+}
+
+/// An animatronic during a night, one of the originals or a pack's.
+pub struct Actor {
+    pub name: String,
+    /// Which of the originals this is, whose room `Positions` mirrors.
+    pub original: Option<Animatronic>,
+    pub room: Room,
+    /// Where a closed door sends it.
+    back: Room,
+    moves: HashMap<Room, Vec<Room>>,
+    /// AI level from 12 AM to 5 AM.
+    levels: [u8; 6],
+    /// Ticks between movement opportunities.
+    pub every: u64,
+    watch: Watch,
+    /// The door it runs at instead of waiting in the doorway.
+    runs: Option<Side>,
+    /// Times it ran into its closed door this night.
+    pub knocks: u32,
+    /// Ticks left before it attacks, once in the office.
+    pub attack: Option<u64>,
+    /// Played from the room it moves into.
+    pub step: Option<String>,
+    /// Camera image by room, for pack animatronics.
+    pub cameras: HashMap<Room, String>,
+    ast: Option<AST>,
+    scope: Scope<'static>,
+    on_move: bool,
+    on_camera: bool,
+    /// The last roll and the AI level it was against.
+    pub roll: Option<(u8, u8)>,
+    /// Percent of power its script or knocking drained this night.
+    pub drained: f32,
+}
+
+impl Actor {
+    fn new(def: &AnimatronicDef, levels: [u8; 6], ast: Option<AST>) -> Actor {
+        let defines = |name| {
+            ast.iter()
+                .flat_map(AST::iter_functions)
+                .any(|f| f.name == name)
+        };
+        Actor {
+            name: def.name.clone(),
+            original: None,
+            room: def.start,
+            back: def.start,
+            moves: def.moves.iter().map(|m| (m.from, m.to.clone())).collect(),
+            levels,
+            every: OPPORTUNITY,
+            watch: Watch::Ignores,
+            runs: None,
+            knocks: 0,
+            attack: None,
+            step: def.sounds.step.clone(),
+            cameras: def
+                .cameras
+                .iter()
+                .filter_map(|(room, image)| Some((Room::parse(room)?, image.clone())))
+                .collect(),
+            on_move: defines("on_move"),
+            on_camera: defines("on_camera"),
+            ast,
+            scope: Scope::new(),
+            roll: None,
+            drained: 0.0,
+        }
+    }
+
+    fn original(original: &Original, levels: [u8; 6]) -> Actor {
+        Actor {
+            name: original.animatronic.name().to_string(),
+            original: Some(original.animatronic),
+            room: original.start,
+            back: original.back,
+            moves: original
+                .moves
+                .iter()
+                .map(|(from, to)| (*from, to.to_vec()))
+                .collect(),
+            levels,
+            every: original.every,
+            watch: original.watch,
+            runs: original.runs,
+            knocks: 0,
+            attack: None,
+            step: None,
+            cameras: HashMap::new(),
+            ast: None,
+            scope: Scope::new(),
+            on_move: false,
+            on_camera: false,
+            roll: None,
+            drained: 0.0,
+        }
+    }
+
+    /// AI level at `hour`, from 0 at 12 AM.
+    pub fn ai(&self, hour: u8) -> u8 {
+        self.levels[hour.min(5) as usize]
+    }
+
+    /// Whether the monitor keeps it from moving.
+    fn held(&self, monitor: &Monitor) -> bool {
+        match self.watch {
+            Watch::Ignores => false,
+            Watch::Room => monitor.watching(self.room),
+            Watch::Monitor => monitor.up,
+        }
+    }
+
+    /// Whether it shows up in the window of `side` while in its doorway.
+    /// Of the originals only the door's visitor does.
+    pub fn shows_at(&self, side: Side) -> bool {
+        self.room == side.doorway() && self.original.is_none_or(|a| a == side.visitor())
+    }
+
+    /// Whether it dashes into the office at its next opportunity, which no
+    /// roll or monitor stops.
+    fn running(&self) -> bool {
+        self.runs.is_some()
+            && self.moves.get(&self.room).is_some_and(|to| to.contains(&Room::Office))
+    }
+
+    /// Moves into `to`. Stepping from a doorway into the office goes through
+    /// `Doors`, and a closed door sends it back. A runner skips the doorway:
+    /// its closed door sends it back too, knocking, and an open one lets it
+    /// attack right away. So does reaching the office with no door on the
+    /// way. Whoever else gets in attacks in `attack` ticks.
+    pub fn enter(&mut self, to: Room, doors: &mut Doors, attack: u64) -> Entered {
+        if let (Some(side), Room::Office) = (self.runs, to) {
+            if doors.get(side).closed {
+                self.room = self.back;
+                return Entered::Knocked(side);
+            }
+            self.room = Room::Office;
+            self.attack = Some(0);
+            return Entered::Office;
+        }
+
+        let door = Side::ALL
+            .into_iter()
+            .find(|side| self.room == side.doorway());
+        if let Some(side) = door {
+            if to == Room::Office {
+                if !doors.enter(side) {
+                    self.room = self.back;
+                    return Entered::Room;
+                }
+                self.room = Room::Office;
+                self.attack = Some(attack);
+                return Entered::Office;
+            }
+            doors.leave(side);
+        }
+
+        self.room = to;
+        if to != Room::Office {
+            return Entered::Room;
+        }
+        self.attack = Some(0);
+        Entered::Office
+    }
+
+    /// Counts down to its attack, cut short by the monitor being `lowered`.
+    /// Returns true once, on the tick it attacks.
+    pub fn attacks(&mut self, lowered: bool) -> bool {
+        match self.attack {
+            Some(0) => {}
+            Some(_) if lowered => {}
+            Some(ticks) => {
+                self.attack = Some(ticks - 1);
+                return false;
+            }
+            None => return false,
+        }
+        self.attack = None;
+        true
+    }
+}
+
+/// Where a move left an animatronic.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Entered {
+    /// Anywhere but the office, or sent back by a closed door.
+    Room,
+    /// In the office, with its attack coming.
+    Office,
+    /// Sent back by the closed door on this side after running at it.
+    Knocked(Side),
+}
+
+/// Every animatronic in the night being played.
+#[derive(Default)]
+pub struct Actors {
+    pub actors: Vec<Actor>,
+    /// The monitor as of the last step, to tell when it changes.
+    camera: Option<Room>,
+}
+
+/// Sets up the originals and compiles the scripts of the selected pack for
+/// the night being played. A pack's night can set the originals' AI levels
+/// too, or they keep the original's.
+fn load(engine: Res<Engine>, mods: Res<Mods>, config: Res<Config>, mut actors: ResMut<Actors>) {
+    *actors = Actors::default();
+    let night = mods.night(config.level());
+    let pack_ai = |name: &str| {
+        let mut levels = [0; 6];
+        let set = night.and_then(|n| n.ai.get(name))?;
+        levels.iter_mut().zip(set).for_each(|(l, ai)| *l = *ai);
+        Some(levels)
+    };
+
+    for original in ORIGINALS.iter() {
+        let levels = pack_ai(original.animatronic.name())
+            .unwrap_or_else(|| ai::levels(original.animatronic, config.level()));
+        actors.actors.push(Actor::original(original, levels));
+    }
+
+    let pack = match mods.current() {
+        Some(pack) => pack,
+        None => return,
+    };
+    for def in pack.manifest.animatronics.iter() {
+        let ast = match &def.script {
+            Some(file) => {
+                let file = pack.dir.join(file);
+                match engine.compile_file(file.clone()) {
+                    Ok(ast) => Some(ast),
+                    Err(e) => {
+                        warn!("{}: {}", file.display(), e);
+                        continue;
+                    }
+                }
+            }
+            None => None,
+        };
+        let levels = pack_ai(&def.name).unwrap_or_default();
+        actors.actors.push(Actor::new(def, levels, ast));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run(
+    engine: Res<Engine>,
+    asr: Res<AssetServer>,
+    tuning: Res<Tuning>,
+    clock: Res<Clock>,
+    monitor: Res<Monitor>,
+    positions: Res<Positions>,
+    mut doors: ResMut<Doors>,
+    mut power: ResMut<Power>,
+    mut rng: ResMut<NightRng>,
+    mut actors: ResMut<Actors>,
+    mut sounds: EventWriter<PlayAt>,
+    mut jumpscares: EventWriter<Jumpscare>,
+) {
+    let camera = monitor.up.then(|| monitor.camera.room());
+    let switched = camera != actors.camera;
+    if switched {
+        actors.camera = camera;
+    }
+    let lowered = switched && camera.is_none();
+    let attack = (tuning.office.attack * RATE as f32) as u64;
+
+    for actor in actors.actors.iter_mut() {
+        if actor.room == Room::Office {
+            if actor.attacks(lowered) {
+                doors.clear_jam();
+                jumpscares.send(Jumpscare(actor.name.clone()));
+            }
+            continue;
+        }
+
+        let opportunity = clock.every(actor.every);
+        if !((switched && actor.on_camera) || opportunity) {
+            continue;
+        }
+
+        let mut api = NightApi {
+            tick: clock.tick,
+            hour: clock.hour(),
+            power: power.0,
+            ai: actor.ai(clock.hour()),
+            room: actor.room,
+            moves: actor.moves.get(&actor.room).cloned().unwrap_or_default(),
+            positions: positions.0,
+            camera,
+            doors: Side::ALL.map(|side| *doors.get(side)),
+            rng: std::mem::replace(&mut rng.rng, StdRng::seed_from_u64(0)),
+            move_to: None,
+            sounds: Vec::new(),
+            drain: 0.0,
+            rolled: None,
+        };
+
+        if switched && actor.on_camera {
+            let room = camera.map(Room::name).unwrap_or_default();
+            api = call(&engine, actor, api, "on_camera", (room.to_string(),));
+        }
+        if opportunity {
+            api = match actor.on_move {
+                true => call(&engine, actor, api, "on_move", ()),
+                false if actor.running() => {
+                    sounds.send(PlayAt {
+                        room: actor.room,
+                        sound: load!(asr, Run),
+                    });
+                    api.move_to = Some(Room::Office);
+                    api
+                }
+                false => {
+                    if !actor.held(&monitor) && api.roll() && !api.moves.is_empty() {
+                        let i = api.rng.gen_range(0..api.moves.len());
+                        api.move_to = Some(api.moves[i]);
+                    }
+                    api
+                }
+            };
+        }
+
+        if let Some(value) = api.rolled {
+            actor.roll = Some((value, api.ai));
+        }
+        rng.rng = api.rng;
+        power.drain(api.drain);
+        actor.drained += api.drain;
+        for sound in api.sounds {
+            sounds.send(PlayAt {
+                room: actor.room,
+                sound: asr.load(&sound),
+            });
+        }
+        if let Some(to) = api.move_to {
+            if let Entered::Knocked(side) = actor.enter(to, &mut doors, attack) {
+                let drain = ai::knock(actor.knocks);
+                actor.knocks += 1;
+                power.drain(drain);
+                actor.drained += drain;
+                sounds.send(PlayAt {
+                    room: side.doorway(),
+                    sound: load!(asr, Knock),
+                });
+            }
+            if let Some(step) = &actor.step {
+                sounds.send(PlayAt {
+                    room: actor.room,
+                    sound: asr.load(step),
+                });
+            }
+        }
+    }
+}
+
+/// Mirrors where the originals are into `Positions` for the cameras and the
+/// office.
+fn place(actors: Res<Actors>, mut positions: ResMut<Positions>) {
+    if !actors.is_changed() {
+        return;
+    }
+    for actor in actors.actors.iter() {
+        if let Some(a) = actor.original {
+            if positions.room(a) != actor.room {
+                positions.set(a, actor.room);
+            }
+        }
+    }
+}
+
+/// Calls `name` with `api` as `this` and hands it back with whatever the
+/// script asked for. A script that fails asks for nothing.
+///
+/// Only called for callbacks the script defines, so there is a script.
+fn call(
+    engine: &Engine,
+    actor: &mut Actor,
+    api: NightApi,
+    name: &str,
+    args: impl rhai::FuncArgs,
+) -> NightApi {
+    let mut this = Dynamic::from(api.clone());
+    let options = CallFnOptions::new().bind_this_ptr(&mut this);
+    let ast = actor.ast.as_ref().expect("a script defining the callback");
+    let called = engine.call_fn_with_options::<Dynamic>(options, &mut actor.scope, ast, name, args);
+
+    match (called, this.try_cast::<NightApi>()) {
+        (Ok(_), Some(asked)) => asked,
+        (called, asked) => {
+            let e = called
+                .err()
+                .map_or("`this` was replaced".to_string(), |e| e.to_string());
+            warn!("{} {}: {}", actor.name, name, e);
+            // keep the dice rolled, so a broken script still replays the same
+            NightApi {
+                rng: asked.map_or(api.rng.clone(), |asked| asked.rng),
+                ..api
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mods::Move;
+
+    fn pack(start: Room) -> Actor {
+        let def = AnimatronicDef {
+            name: "springtrap".to_string(),
+            start,
+            moves: vec![Move {
+                from: Room::LeftDoor,
+                to: vec![Room::Office],
+            }],
+            jumpscare: None,
+            sounds: default(),
+            cameras: HashMap::new(),
+            script: None,
+        };
+        Actor::new(&def, [20; 6], None)
+    }
+
+    #[test]
+    fn attacks_with_the_monitor_never_raised() {
+        let mut doors = Doors::default();
+        let mut actor = pack(Room::LeftDoor);
+
+        assert_eq!(actor.enter(Room::Office, &mut doors, 3), Entered::Office);
+        assert!(doors.jammed());
+        let attacks: Vec<_> = (0..5).map(|_| actor.attacks(false)).collect();
+        assert_eq!(attacks, [false, false, false, true, false]);
+    }
+
+    #[test]
+    fn lowering_the_monitor_attacks_early() {
+        let mut doors = Doors::default();
+        let mut actor = pack(Room::LeftDoor);
+
+        actor.enter(Room::Office, &mut doors, 100);
+        assert!(!actor.attacks(false));
+        assert!(actor.attacks(true));
+        assert!(!actor.attacks(true));
+    }
+
+    #[test]
+    fn a_closed_door_sends_them_back() {
+        let mut doors = Doors::default();
+        doors.toggle_door(Side::Left);
+        let mut actor = pack(Room::LeftDoor);
+        actor.back = Room::DiningArea;
+
+        assert_eq!(actor.enter(Room::Office, &mut doors, 3), Entered::Room);
+        assert_eq!(actor.room, Room::DiningArea);
+        assert_eq!(actor.attack, None);
+    }
+
+    #[test]
+    fn foxy_runs_at_the_left_door() {
+        let foxy = ORIGINALS
+            .iter()
+            .find(|o| o.animatronic == Animatronic::Foxy)
+            .unwrap();
+        let mut doors = Doors::default();
+        doors.toggle_door(Side::Left);
+        let mut actor = Actor::original(foxy, [20; 6]);
+        actor.room = Room::WestHall;
+        assert!(actor.running());
+
+        assert_eq!(
+            actor.enter(Room::Office, &mut doors, 100),
+            Entered::Knocked(Side::Left)
+        );
+        assert_eq!(actor.room, Room::PirateCove);
+        assert!(!actor.running());
+
+        doors.toggle_door(Side::Left);
+        actor.room = Room::WestHall;
+        assert_eq!(actor.enter(Room::Office, &mut doors, 100), Entered::Office);
+        assert!(!doors.jammed());
+        assert!(actor.attacks(false));
+    }
+}
@@ -0,0 +1,217 @@
+use bevy::{core::FixedTimestep, prelude::*};
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{
+    assets::GameAssets,
+    audio::{Channel, PlaySound},
+    doors::{Doors, Side},
+    map::Monitor,
+    mods::Mods,
+    pause::Pausable,
+    save::Config,
+    GameState,
+};
+
+/// Ticks per second of night time.
+pub const RATE: u64 = 60;
+
+/// A night hour lasts 89 seconds, like the original.
+pub const HOUR: u64 = 89 * RATE;
+
+/// How many times faster hours go with the fast nights cheat.
+const FAST: u64 = 4;
+
+/// The phone call at the start of nights 1 to 5.
+const CALLS: [GameAssets; 5] = [
+    GameAssets::Call1,
+    GameAssets::Call2,
+    GameAssets::Call3,
+    GameAssets::Call4,
+    GameAssets::Call5,
+];
+
+/// The night's clock, power and dice. Everything that decides how a night
+/// plays out runs in `NightStage`, a fixed step of `1 / RATE` seconds, and
+/// draws from `NightRng`, so the same seed and inputs replay the same night.
+pub struct NightPlugin;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, StageLabel)]
+pub struct NightStage;
+
+/// Runs first in every night step.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, SystemLabel)]
+pub struct Tick;
+
+impl Plugin for NightPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Clock>()
+            .init_resource::<Power>()
+            .init_resource::<NightRng>()
+            .add_event::<Dawn>()
+            .add_stage_after(
+                CoreStage::Update,
+                NightStage,
+                SystemStage::parallel().with_run_criteria(FixedTimestep::step(1.0 / RATE as f64)),
+            )
+            .add_system_set(
+                SystemSet::on_enter(GameState::Frame1)
+                    .with_system(start)
+                    .with_system(call),
+            )
+            .add_system_set_to_stage(
+                NightStage,
+                SystemSet::on_update(GameState::Frame1)
+                    .with_system(tick.label(Tick))
+                    .with_system(drain.after(Tick))
+                    .with_system(hold.after(drain)),
+            );
+    }
+}
+
+/// Sent on the tick the clock strikes 6 AM: the night is survived.
+pub struct Dawn;
+
+#[derive(Debug)]
+pub struct Clock {
+    pub tick: u64,
+    /// Ticks an hour lasts, `HOUR` unless fast nights are on.
+    pub length: u64,
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Clock {
+            tick: 0,
+            length: HOUR,
+        }
+    }
+}
+
+impl Clock {
+    /// 0 at 12 AM, 6 at 6 AM.
+    pub fn hour(&self) -> u8 {
+        (self.tick / self.length).min(6) as u8
+    }
+
+    /// Whether this tick is one of every `ticks`, the way movement
+    /// opportunities come around.
+    pub fn every(&self, ticks: u64) -> bool {
+        ticks > 0 && self.tick > 0 && self.tick.is_multiple_of(ticks)
+    }
+}
+
+/// Power left, in percent.
+#[derive(Debug)]
+pub struct Power(pub f32);
+
+impl Default for Power {
+    fn default() -> Self {
+        Power(100.0)
+    }
+}
+
+impl Power {
+    /// The original loses 1% every 9.6 seconds per bar of usage.
+    pub const PER_BAR: f32 = 1.0 / (9.6 * RATE as f32);
+
+    /// Bars of usage: one for being on, one more for every closed door, lit
+    /// hall and the monitor.
+    pub fn usage(doors: &Doors, monitor: &Monitor) -> u32 {
+        let sides = Side::ALL.map(|side| doors.get(side));
+        1 + sides.iter().filter(|d| d.closed).count() as u32
+            + sides.iter().filter(|d| d.light).count() as u32
+            + monitor.up as u32
+    }
+
+    /// Percent the office drains a tick, times the night's `multiplier`.
+    pub fn rate(doors: &Doors, monitor: &Monitor, multiplier: f32) -> f32 {
+        Power::usage(doors, monitor) as f32 * Power::PER_BAR * multiplier
+    }
+
+    pub fn drain(&mut self, percent: f32) {
+        self.0 = (self.0 - percent).max(0.0);
+    }
+}
+
+/// The night's random numbers. Its seed is all a replay needs besides the
+/// inputs.
+pub struct NightRng {
+    pub seed: u64,
+    pub rng: StdRng,
+}
+
+impl NightRng {
+    pub fn new(seed: u64) -> NightRng {
+        NightRng {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl Default for NightRng {
+    fn default() -> Self {
+        NightRng::new(0)
+    }
+}
+
+fn start(
+    config: Res<Config>,
+    mut clock: ResMut<Clock>,
+    mut power: ResMut<Power>,
+    mut rng: ResMut<NightRng>,
+    mut pausable: ResMut<Pausable>,
+) {
+    pausable.0 = true;
+    *clock = Clock {
+        tick: 0,
+        length: match config.cheats().fast_nights {
+            true => HOUR / FAST,
+            false => HOUR,
+        },
+    };
+    *power = Power::default();
+    *rng = NightRng::new(rand::random());
+    info!("night seed {}", rng.seed);
+}
+
+fn call(asr: Res<AssetServer>, config: Res<Config>, mut sounds: EventWriter<PlaySound>) {
+    let night = (config.level() as usize).checked_sub(1);
+    if let Some(call) = night.and_then(|i| CALLS.get(i)) {
+        sounds.send(PlaySound {
+            channel: Channel::Voice,
+            sound: asr.load(call.file()),
+            looped: false,
+        });
+    }
+}
+
+fn tick(mut clock: ResMut<Clock>, mut dawn: EventWriter<Dawn>) {
+    clock.tick += 1;
+    if clock.tick == 6 * clock.length {
+        dawn.send(Dawn);
+    }
+}
+
+fn drain(
+    config: Res<Config>,
+    mods: Res<Mods>,
+    doors: Res<Doors>,
+    monitor: Res<Monitor>,
+    mut power: ResMut<Power>,
+) {
+    let rate = Power::rate(&doors, &monitor, mods.drain(config.level()));
+    match config.cheats().infinite_power {
+        // scripts drain power too, so top it back up
+        true => *power = Power::default(),
+        false => power.drain(rate),
+    }
+}
+
+/// The pause menu stays shut through the 5 AM to 6 AM chimes and once the
+/// power is out, like the original. The next night opens it again.
+fn hold(clock: Res<Clock>, power: Res<Power>, mut pausable: ResMut<Pausable>) {
+    if pausable.0 && (clock.hour() >= 5 || power.0 <= 0.0) {
+        pausable.0 = false;
+    }
+}
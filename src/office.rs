@@ -0,0 +1,195 @@
+use bevy::prelude::*;
+
+use crate::{
+    assets,
+    counter::{Align, Bind, Counter, CounterApp},
+    despawn_unload,
+    doors::{DoorButton, LightButton, Side},
+    locale::Locale,
+    night::{Clock, Power},
+    palette::Palette,
+    save::Config,
+    tuning::Tuning,
+    warp::{self, Pan, WarpMaterial},
+    GameState,
+};
+
+/// The office: a panorama wider than the window, scrolled by holding the
+/// cursor towards either edge, with the hour and the power left in the
+/// corners.
+pub struct OfficePlugin;
+
+impl Plugin for OfficePlugin {
+    fn build(&self, app: &mut App) {
+        app.bind_counter::<Clock, u32>()
+            .bind_counter::<Power, u32>()
+            .add_system_set(
+                SystemSet::on_enter(GameState::Frame1)
+                    .with_system(setup)
+                    .with_system(buttons)
+                    .with_system(hud),
+            )
+            .add_system_set(SystemSet::on_update(GameState::Frame1).with_system(follow_cursor))
+            .add_system_set(
+                SystemSet::on_exit(GameState::Frame1).with_system(despawn_unload::<OnOfficeScreen>),
+            );
+    }
+}
+
+#[derive(Component, Default)]
+pub struct OnOfficeScreen;
+
+/// The office panorama, as opposed to a camera feed.
+#[derive(Component)]
+pub struct Office;
+
+fn setup(
+    mut commands: Commands,
+    asr: Res<AssetServer>,
+    tuning: Res<Tuning>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<WarpMaterial>>,
+) {
+    commands
+        .spawn_bundle(OrthographicCameraBundle::new_2d())
+        .insert(OnOfficeScreen);
+
+    let material = materials.add(WarpMaterial::new(load!(asr, Office), tuning.office.warp));
+    commands
+        .spawn_bundle(warp::bundle(&mut meshes, material, 0.0))
+        // the original starts looking at the middle of the office
+        .insert(Pan::new(0.5))
+        .insert(Office)
+        .insert(OnOfficeScreen);
+}
+
+/// The hour in the top right corner and the power left in the bottom left.
+/// They use the title's digits until the night's own are in.
+fn hud(mut commands: Commands, asr: Res<AssetServer>, mut atlases: ResMut<Assets<TextureAtlas>>) {
+    let sheet = assets::NIGHT_NUMBER_TITLE_FRAMES;
+    let texture_atlas = atlases.add(sheet.atlas(load!(asr, NightNumberTitleFrames)));
+    let advance = sheet.tile[0] as f32;
+
+    commands
+        .spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(
+            600.0, 320.0, 10.0,
+        )))
+        .insert(Counter::new(texture_atlas.clone(), 12u32, advance).align(Align::Right))
+        .insert(Bind::<Clock, u32>(|clock| match clock.hour() {
+            0 => 12,
+            hour => hour.into(),
+        }))
+        .insert(OnOfficeScreen);
+
+    commands
+        .spawn_bundle(TransformBundle::from_transform(Transform::from_xyz(
+            -600.0, -320.0, 10.0,
+        )))
+        // 0 only once the power is out
+        .insert(Counter::new(texture_atlas, 100u32, advance))
+        .insert(Bind::<Power, u32>(|power| power.0.ceil() as u32))
+        .insert(OnOfficeScreen);
+}
+
+/// The door and light buttons, a pair on each edge of the window.
+fn buttons(mut commands: Commands, config: Res<Config>, locale: Res<Locale>) {
+    let idle = Palette::get(&config).idle;
+    let button = |key: &str| {
+        (
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(72.0), Val::Px(48.0)),
+                    margin: Rect::all(Val::Px(6.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                color: UiColor(idle),
+                ..default()
+            },
+            TextBundle {
+                text: Text::with_section(
+                    locale.text(key),
+                    TextStyle {
+                        font: locale.font.clone(),
+                        font_size: 16.0,
+                        color: Color::BLACK,
+                    },
+                    default(),
+                ),
+                ..default()
+            },
+        )
+    };
+
+    for side in Side::ALL {
+        let position = match side {
+            Side::Left => Rect {
+                left: Val::Px(10.0),
+                ..default()
+            },
+            Side::Right => Rect {
+                right: Val::Px(10.0),
+                ..default()
+            },
+        };
+
+        commands
+            .spawn_bundle(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    position,
+                    size: Size::new(Val::Auto, Val::Percent(100.0)),
+                    flex_direction: FlexDirection::ColumnReverse,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                color: UiColor(Color::NONE),
+                ..default()
+            })
+            .insert(OnOfficeScreen)
+            .with_children(|parent| {
+                let (bundle, label) = button("office.door");
+                parent
+                    .spawn_bundle(bundle)
+                    .insert(DoorButton(side))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(label);
+                    });
+                let (bundle, label) = button("office.light");
+                parent
+                    .spawn_bundle(bundle)
+                    .insert(LightButton(side))
+                    .with_children(|parent| {
+                        parent.spawn_bundle(label);
+                    });
+            });
+    }
+}
+
+/// Scrolls faster the closer the cursor is to an edge, and not at all while
+/// it is within the middle `dead_zone` of the window.
+fn follow_cursor(
+    time: Res<Time>,
+    windows: Res<Windows>,
+    tuning: Res<Tuning>,
+    mut query: Query<&mut Pan, With<Office>>,
+) {
+    let window = windows.primary();
+    let cursor = match window.cursor_position() {
+        Some(cursor) => cursor,
+        None => return,
+    };
+
+    // -1 at the left edge, 1 at the right
+    let x = (cursor.x / window.width()) * 2.0 - 1.0;
+    let dead = tuning.office.dead_zone;
+    let push = match x.abs() > dead {
+        true => x.signum() * (x.abs() - dead) / (1.0 - dead),
+        false => return,
+    };
+
+    for mut pan in query.iter_mut() {
+        pan.scroll(push * tuning.office.pan * time.delta_seconds());
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+use crate::{
+    animation::{Animated, AnimationPlugin},
+    assets::{self, GameAssets, Sheet},
+    save::Config,
+    tuning::Tuning,
+    GameState,
+};
+
+/// Full-screen flickering layers from the title, for any state that wants
+/// them: static over camera feeds, blips between cameras, the game over
+/// screen.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Effect {
+    Freddy,
+    Static,
+    Blip,
+}
+
+impl Effect {
+    fn sheet(self) -> (GameAssets, Sheet) {
+        match self {
+            Effect::Freddy => (GameAssets::FreddyFrames, assets::FREDDY_FRAMES),
+            Effect::Static => (GameAssets::StaticFrames, assets::STATIC_FRAMES),
+            Effect::Blip => (GameAssets::BlipFrames, assets::BLIP_FRAMES),
+        }
+    }
+
+    /// Name of the `[animation.<name>]` tuning table.
+    fn animation(self) -> &'static str {
+        match self {
+            Effect::Freddy => "freddy",
+            Effect::Static => "static",
+            Effect::Blip => "blip",
+        }
+    }
+
+    /// Stacked in declaration order, Freddy at the back.
+    fn z(self) -> f32 {
+        match self {
+            Effect::Freddy => 0.0,
+            Effect::Static => 0.1,
+            Effect::Blip => 0.2,
+        }
+    }
+}
+
+/// One of the effects, for whoever owns it to show and hide with
+/// `Animated::show`.
+#[derive(Component)]
+pub struct Overlay(pub Effect);
+
+/// What each state overlays. States without one show none.
+#[derive(Default)]
+pub struct Overlays(HashMap<GameState, Vec<Effect>>);
+
+pub trait OverlayApp {
+    /// Spawns `effects` on entering `state`, tagged with the screen's `T` so
+    /// they go with its `despawn_unload::<T>`, and animates them while the
+    /// state is active.
+    fn add_overlay<T: Component + Default>(
+        &mut self,
+        state: GameState,
+        effects: &[Effect],
+    ) -> &mut Self;
+}
+
+impl OverlayApp for App {
+    fn add_overlay<T: Component + Default>(
+        &mut self,
+        state: GameState,
+        effects: &[Effect],
+    ) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(Overlays::default)
+            .0
+            .insert(state.clone(), effects.to_vec());
+        self.add_plugin(AnimationPlugin(state.clone()))
+            .add_system_set(SystemSet::on_enter(state).with_system(spawn::<T>))
+    }
+}
+
+fn spawn<T: Component + Default>(
+    mut commands: Commands,
+    asr: Res<AssetServer>,
+    state: Res<State<GameState>>,
+    overlays: Res<Overlays>,
+    tuning: Res<Tuning>,
+    config: Res<Config>,
+    mut textures: ResMut<Assets<TextureAtlas>>,
+) {
+    let effects = match overlays.0.get(state.current()) {
+        Some(effects) => effects,
+        None => return,
+    };
+
+    for effect in effects {
+        spawn_effect(
+            &mut commands,
+            *effect,
+            &asr,
+            &mut textures,
+            &tuning,
+            &config,
+        )
+        .insert(T::default());
+    }
+}
+
+/// Spawns one effect outside of `add_overlay`, like the jumpscare's static.
+/// It only animates in states with an `AnimationPlugin`.
+pub fn spawn_effect<'w, 's, 'a>(
+    commands: &'a mut Commands<'w, 's>,
+    effect: Effect,
+    asr: &AssetServer,
+    textures: &mut Assets<TextureAtlas>,
+    tuning: &Tuning,
+    config: &Config,
+) -> EntityCommands<'w, 's, 'a> {
+    let (asset, sheet) = effect.sheet();
+    let texture_atlas = sheet.atlas(asr.load(asset.path()));
+
+    let mut entity = commands.spawn_bundle(SpriteSheetBundle {
+        texture_atlas: textures.add(texture_atlas),
+        transform: Transform::from_xyz(0.0, 0.0, effect.z()),
+        ..default()
+    });
+    entity
+        .insert(Animated::new(effect.animation(), tuning, config))
+        .insert(Overlay(effect));
+    entity
+}
@@ -0,0 +1,181 @@
+use std::path::{Path, PathBuf};
+
+use image::{imageops, DynamicImage, GenericImageView, RgbaImage};
+
+use crate::assets::{GameAssets, SHEETS};
+
+/// Where the frames of a sheet come from in a rip, in atlas order.
+enum Source {
+    Files(&'static [&'static str]),
+    /// Every png in the directory, ordered by the number in its file name.
+    Dir(&'static str),
+}
+
+/// Frames for each sheet in `assets::SHEETS`, by `GameAssets` variant name.
+const RECIPES: &[(&str, Source)] = &[
+    (
+        "StaticFrames",
+        Source::Files(&[
+            "images/Title/12.png",
+            "images/Title/13.png",
+            "images/Title/14.png",
+            "images/Title/15.png",
+            "images/Title/16.png",
+            "images/Title/17.png",
+            "images/Title/18.png",
+            "images/Title/20.png",
+            "images/Title/22.png",
+        ]),
+    ),
+    (
+        "BlipFrames",
+        Source::Files(&[
+            "images/Title/433.png",
+            "images/Title/434.png",
+            "images/Title/435.png",
+            "images/Title/436.png",
+            "images/Title/437.png",
+            "images/Title/438.png",
+            "images/Title/439.png",
+            "images/Title/445.png",
+        ]),
+    ),
+    (
+        "FreddyFrames",
+        Source::Files(&[
+            // mouth closed, mouth open, turned, exo
+            "images/Title/431.png",
+            "images/Title/440.png",
+            "images/Title/441.png",
+            "images/Title/442.png",
+        ]),
+    ),
+    ("NightNumberTitleFrames", Source::Dir("images/NightNumbers")),
+];
+
+/// `frnaf pack-sheets [rip dir] [assets dir]`
+///
+/// Packs individually ripped frames into the sprite sheets the game loads
+/// and writes them into the asset root.
+pub fn run(rip: Option<PathBuf>, out: Option<PathBuf>) -> bool {
+    let rip = rip.unwrap_or_else(crate::check::default_root);
+    let out = out.unwrap_or_else(|| rip.clone());
+    println!(
+        "packing frames from {} into {}",
+        rip.display(),
+        out.display()
+    );
+
+    let mut ok = true;
+    for (name, source) in RECIPES {
+        let path = match GameAssets::ALL.iter().find(|(n, _)| n == name) {
+            Some((_, path)) => out.join(path),
+            None => continue,
+        };
+
+        match pack(&rip, name, source) {
+            Ok(sheet) => match sheet.save(&path) {
+                Ok(()) => println!("  wrote    {}", path.display()),
+                Err(e) => {
+                    ok = false;
+                    println!("  PROBLEM  {}: can't write: {}", path.display(), e);
+                }
+            },
+            Err(e) => {
+                ok = false;
+                println!("  PROBLEM  {}: {}", name, e);
+            }
+        }
+    }
+
+    ok
+}
+
+fn pack(rip: &Path, name: &str, source: &Source) -> Result<RgbaImage, String> {
+    let frames: Vec<PathBuf> = match source {
+        Source::Files(files) => files.iter().map(|f| rip.join(f)).collect(),
+        Source::Dir(dir) => numbered(&rip.join(dir))?,
+    };
+
+    pack_frames(name, &frames, |frame| {
+        image::open(frame).map_err(|e| format!("can't decode {}: {}", frame.display(), e))
+    })
+}
+
+/// Packs a sheet whose recipe lists its frames by asset path, loading each
+/// frame with `load`. `None` if the sheet has no such recipe.
+pub fn pack_files(
+    name: &str,
+    load: impl Fn(&Path) -> Result<DynamicImage, String>,
+) -> Option<Result<RgbaImage, String>> {
+    match RECIPES.iter().find(|(n, _)| *n == name) {
+        Some((_, Source::Files(files))) => {
+            let frames: Vec<&Path> = files.iter().map(Path::new).collect();
+            Some(pack_frames(name, &frames, load))
+        }
+        _ => None,
+    }
+}
+
+fn pack_frames<P: AsRef<Path>>(
+    name: &str,
+    frames: &[P],
+    load: impl Fn(&Path) -> Result<DynamicImage, String>,
+) -> Result<RgbaImage, String> {
+    let sheet = match SHEETS.iter().find(|(n, _)| *n == name) {
+        Some((_, sheet)) => sheet,
+        None => return Err("no grid in assets::SHEETS".to_string()),
+    };
+
+    if frames.len() > sheet.columns * sheet.rows {
+        return Err(format!(
+            "{} frames don't fit a {}x{} grid",
+            frames.len(),
+            sheet.columns,
+            sheet.rows
+        ));
+    }
+
+    let [width, height] = sheet.size();
+    let mut packed = RgbaImage::new(width, height);
+    for (i, frame) in frames.iter().enumerate() {
+        let frame = frame.as_ref();
+        let image = load(frame)?;
+        if [image.width(), image.height()] != sheet.tile {
+            return Err(format!(
+                "{} is {}x{}, expected {}x{}",
+                frame.display(),
+                image.width(),
+                image.height(),
+                sheet.tile[0],
+                sheet.tile[1]
+            ));
+        }
+
+        let x = (i % sheet.columns) as u32 * sheet.tile[0];
+        let y = (i / sheet.columns) as u32 * sheet.tile[1];
+        imageops::replace(&mut packed, &image.to_rgba8(), x, y);
+    }
+
+    Ok(packed)
+}
+
+fn numbered(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+
+    let mut frames: Vec<(u32, PathBuf)> = entries
+        .filter_map(|e| e.ok().map(|e| e.path()))
+        .filter(|p| p.extension().is_some_and(|e| e == "png"))
+        .filter_map(|p| {
+            let n = p.file_stem()?.to_str()?.parse().ok()?;
+            Some((n, p))
+        })
+        .collect();
+    frames.sort_by_key(|(n, _)| *n);
+
+    if frames.is_empty() {
+        return Err(format!("no numbered frames in {}", dir.display()));
+    }
+
+    Ok(frames.into_iter().map(|(_, p)| p).collect())
+}
@@ -0,0 +1,119 @@
+use bevy::prelude::*;
+
+use crate::{
+    doors::{DoorButton, Doors, LightButton},
+    map::{CameraButton, Monitor},
+    mods::script::Actors,
+    save::Config,
+    GameState,
+};
+
+/// Tints the door, light and camera buttons by state, in colors picked by the
+/// colorblind option. With the radar cheat, cameras on an animatronic, a
+/// mod's included, are tinted too.
+pub struct PalettePlugin;
+
+impl Plugin for PalettePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system_set(
+            SystemSet::on_update(GameState::Frame1)
+                .with_system(doors)
+                .with_system(cameras),
+        );
+    }
+}
+
+pub struct Palette {
+    /// A closed door.
+    pub door: Color,
+    /// A lit hall light.
+    pub light: Color,
+    /// The camera on the monitor.
+    pub camera: Color,
+    /// A camera with an animatronic in view, for the radar cheat.
+    pub radar: Color,
+    /// Anything switched off.
+    pub idle: Color,
+}
+
+impl Palette {
+    /// The original's red doors and green camera.
+    pub const STANDARD: Palette = Palette {
+        door: Color::rgb(0.9, 0.1, 0.1),
+        light: Color::rgb(1.0, 1.0, 0.85),
+        camera: Color::rgb(0.2, 0.8, 0.2),
+        radar: Color::rgb(0.9, 0.5, 0.1),
+        idle: Color::rgb(0.5, 0.5, 0.5),
+    };
+
+    /// Okabe and Ito's orange, sky blue, blue and reddish purple, apart for
+    /// every kind of color blindness.
+    pub const COLORBLIND: Palette = Palette {
+        door: Color::rgb(0.9, 0.62, 0.0),
+        light: Color::rgb(0.34, 0.71, 0.91),
+        camera: Color::rgb(0.0, 0.45, 0.7),
+        radar: Color::rgb(0.8, 0.47, 0.65),
+        idle: Color::rgb(0.5, 0.5, 0.5),
+    };
+
+    pub fn get(config: &Config) -> &'static Palette {
+        match config.accessibility().colorblind {
+            true => &Palette::COLORBLIND,
+            false => &Palette::STANDARD,
+        }
+    }
+}
+
+/// Door and light buttons spawned this frame.
+type NewButtons = Or<(Added<DoorButton>, Added<LightButton>)>;
+
+fn doors(
+    config: Res<Config>,
+    state: Res<Doors>,
+    mut doors: Query<(&DoorButton, &mut UiColor), Without<LightButton>>,
+    mut lights: Query<(&LightButton, &mut UiColor), Without<DoorButton>>,
+    added: Query<(), NewButtons>,
+) {
+    if !(state.is_changed() || config.is_changed() || !added.is_empty()) {
+        return;
+    }
+
+    let palette = Palette::get(&config);
+    for (button, mut color) in doors.iter_mut() {
+        color.0 = match state.get(button.0).closed {
+            true => palette.door,
+            false => palette.idle,
+        };
+    }
+    for (button, mut color) in lights.iter_mut() {
+        color.0 = match state.get(button.0).light {
+            true => palette.light,
+            false => palette.idle,
+        };
+    }
+}
+
+fn cameras(
+    config: Res<Config>,
+    monitor: Res<Monitor>,
+    actors: Res<Actors>,
+    mut buttons: Query<(&CameraButton, &mut UiColor)>,
+    added: Query<(), Added<CameraButton>>,
+) {
+    let changed = monitor.is_changed() || actors.is_changed() || config.is_changed();
+    if !changed && added.is_empty() {
+        return;
+    }
+
+    let palette = Palette::get(&config);
+    let radar = config.cheats().radar;
+    for (button, mut color) in buttons.iter_mut() {
+        let room = button.0.room();
+        let seen = actors.actors.iter().any(|a| a.room == room);
+        color.0 = match (button.0 == monitor.camera, radar && seen) {
+            (true, _) => palette.camera,
+            (false, true) => palette.radar,
+            (false, false) => palette.idle,
+        };
+    }
+}
@@ -0,0 +1,410 @@
+use bevy::{app::AppExit, prelude::*};
+
+use crate::{
+    audio::Mixer,
+    despawn_screen,
+    input::{Action, Binding, Rebind},
+    locale::{Locale, LANGUAGES},
+    save::Config,
+    transition::Transition,
+    GameState,
+};
+
+/// The pause menu, pushed on top of a night.
+///
+/// While it is up the night's `on_update` systems don't run, which stops the
+/// clock, the animatronics and the power; night systems must not use
+/// `on_inactive_update` for anything that should freeze.
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Pausable(true))
+            .init_resource::<PauseMenu>()
+            .add_system(pause)
+            .add_system_set(SystemSet::on_enter(GameState::Pause).with_system(setup))
+            .add_system_set(
+                SystemSet::on_update(GameState::Pause)
+                    .with_system(hover)
+                    .with_system(navigate.after(hover))
+                    .with_system(render.after(navigate)),
+            )
+            .add_system_set(SystemSet::on_exit(GameState::Pause).with_system(exit));
+    }
+}
+
+/// States the pause menu can be opened from.
+const NIGHTS: &[GameState] = &[GameState::Frame1];
+
+/// Cleared while pausing would break the original's rules: by a jumpscare,
+/// and by the night from 5 AM on and once the power is out.
+pub struct Pausable(pub bool);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Entry {
+    Resume,
+    Options,
+    QuitToTitle,
+    QuitGame,
+}
+
+impl Entry {
+    const ALL: [Entry; 4] = [
+        Entry::Resume,
+        Entry::Options,
+        Entry::QuitToTitle,
+        Entry::QuitGame,
+    ];
+
+    fn key(self) -> &'static str {
+        match self {
+            Entry::Resume => "pause.resume",
+            Entry::Options => "pause.options",
+            Entry::QuitToTitle => "pause.quit_to_title",
+            Entry::QuitGame => "pause.quit_game",
+        }
+    }
+}
+
+/// Rows after the bindings on the options page, confirm to change them.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Setting {
+    Language,
+    Captions,
+    CaptionSize,
+    ReducedFlashing,
+    SoftenJumpscares,
+    Colorblind,
+}
+
+impl Setting {
+    const ALL: [Setting; 6] = [
+        Setting::Language,
+        Setting::Captions,
+        Setting::CaptionSize,
+        Setting::ReducedFlashing,
+        Setting::SoftenJumpscares,
+        Setting::Colorblind,
+    ];
+
+    fn label(self, config: &Config, locale: &Locale) -> String {
+        let captions = config.captions();
+        let accessibility = config.accessibility();
+        let toggle = |key: &str, on: bool| {
+            let state = match on {
+                true => locale.text("pause.on"),
+                false => locale.text("pause.off"),
+            };
+            locale.fill(key, &[("state", state)])
+        };
+        match self {
+            Setting::Language => {
+                locale.fill("pause.language", &[("language", &locale.strings.name)])
+            }
+            Setting::Captions => toggle("pause.captions", captions.enabled),
+            Setting::CaptionSize => locale.fill(
+                "pause.caption_size",
+                &[("size", &format!("{}", captions.size))],
+            ),
+            Setting::ReducedFlashing => {
+                toggle("pause.reduced_flashing", accessibility.reduced_flashing)
+            }
+            Setting::SoftenJumpscares => {
+                toggle("pause.soften_jumpscares", accessibility.soften_jumpscares)
+            }
+            Setting::Colorblind => toggle("pause.colorblind", accessibility.colorblind),
+        }
+    }
+
+    fn change(self, config: &mut Config) {
+        let mut captions = config.captions();
+        let mut accessibility = config.accessibility();
+        match self {
+            Setting::Language => {
+                let i = LANGUAGES
+                    .iter()
+                    .position(|(code, _)| *code == config.language())
+                    .map_or(0, |i| (i + 1) % LANGUAGES.len());
+                config.set_language(LANGUAGES[i].0.to_string());
+            }
+            Setting::Captions => {
+                captions.enabled = !captions.enabled;
+                config.set_captions(captions);
+            }
+            Setting::CaptionSize => {
+                let i = CAPTION_SIZES
+                    .iter()
+                    .position(|size| *size == captions.size)
+                    .map_or(0, |i| (i + 1) % CAPTION_SIZES.len());
+                captions.size = CAPTION_SIZES[i];
+                config.set_captions(captions);
+            }
+            Setting::ReducedFlashing => {
+                accessibility.reduced_flashing = !accessibility.reduced_flashing;
+                config.set_accessibility(accessibility);
+            }
+            Setting::SoftenJumpscares => {
+                accessibility.soften_jumpscares = !accessibility.soften_jumpscares;
+                config.set_accessibility(accessibility);
+            }
+            Setting::Colorblind => {
+                accessibility.colorblind = !accessibility.colorblind;
+                config.set_accessibility(accessibility);
+            }
+        }
+    }
+}
+
+const CAPTION_SIZES: [f32; 4] = [18.0, 24.0, 32.0, 40.0];
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Page {
+    Main,
+    /// One row per binding, confirm to rebind it, then the `Setting`s.
+    Options,
+}
+
+struct PauseMenu {
+    page: Page,
+    selected: usize,
+}
+
+impl Default for PauseMenu {
+    fn default() -> Self {
+        PauseMenu {
+            page: Page::Main,
+            selected: 0,
+        }
+    }
+}
+
+impl PauseMenu {
+    fn rows(&self, config: &Config) -> usize {
+        match self.page {
+            Page::Main => Entry::ALL.len(),
+            Page::Options => config.bindings().len() + Setting::ALL.len(),
+        }
+    }
+}
+
+#[derive(Component)]
+struct OnPauseScreen;
+
+#[derive(Component)]
+struct Menu;
+
+#[derive(Component)]
+struct Row(usize);
+
+fn pause(
+    actions: Res<Input<Action>>,
+    pausable: Res<Pausable>,
+    state: Res<State<GameState>>,
+    mut transitions: EventWriter<Transition>,
+) {
+    if actions.just_pressed(Action::Back) && pausable.0 && NIGHTS.contains(state.current()) {
+        transitions.send(Transition::Push(GameState::Pause));
+    }
+}
+
+fn setup(
+    mut commands: Commands,
+    mut actions: ResMut<Input<Action>>,
+    mut menu: ResMut<PauseMenu>,
+    mut mixer: ResMut<Mixer>,
+) {
+    // the Back that opened the menu shouldn't also close it
+    actions.clear();
+    mixer.paused = true;
+    *menu = PauseMenu::default();
+
+    commands
+        .spawn_bundle(UiCameraBundle::default())
+        .insert(OnPauseScreen);
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                position_type: PositionType::Absolute,
+                flex_direction: FlexDirection::ColumnReverse,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: UiColor(Color::rgba(0.0, 0.0, 0.0, 0.75)),
+            ..default()
+        })
+        .insert(Menu)
+        .insert(OnPauseScreen);
+}
+
+fn exit(
+    commands: Commands,
+    to_despawn: Query<Entity, With<OnPauseScreen>>,
+    mut mixer: ResMut<Mixer>,
+    mut rebind: ResMut<Rebind>,
+) {
+    mixer.paused = false;
+    rebind.0 = None;
+    despawn_screen(to_despawn, commands);
+}
+
+fn hover(rows: Query<(&Interaction, &Row), Changed<Interaction>>, mut menu: ResMut<PauseMenu>) {
+    for (interaction, row) in rows.iter() {
+        if *interaction != Interaction::None && menu.selected != row.0 {
+            menu.selected = row.0;
+        }
+    }
+}
+
+fn navigate(
+    actions: Res<Input<Action>>,
+    mut config: ResMut<Config>,
+    mut menu: ResMut<PauseMenu>,
+    mut rebind: ResMut<Rebind>,
+    mut transitions: EventWriter<Transition>,
+    mut quit: EventWriter<AppExit>,
+    clicks: Query<&Interaction, (Changed<Interaction>, With<Row>)>,
+) {
+    if rebind.0.is_some() {
+        return;
+    }
+
+    let rows = menu.rows(&config);
+    if actions.just_pressed(Action::Up) {
+        menu.selected = (menu.selected + rows - 1) % rows;
+    }
+    if actions.just_pressed(Action::Down) {
+        menu.selected = (menu.selected + 1) % rows;
+    }
+
+    if actions.just_pressed(Action::Back) {
+        match menu.page {
+            Page::Main => transitions.send(Transition::Pop),
+            Page::Options => {
+                menu.page = Page::Main;
+                menu.selected = Entry::Options as usize;
+            }
+        }
+        return;
+    }
+
+    // `hover` has already selected the clicked row
+    let clicked = clicks.iter().any(|i| *i == Interaction::Clicked);
+    if !(actions.just_pressed(Action::Confirm) || clicked) {
+        return;
+    }
+
+    match menu.page {
+        Page::Main => match Entry::ALL[menu.selected] {
+            Entry::Resume => transitions.send(Transition::Pop),
+            Entry::Options => {
+                menu.page = Page::Options;
+                menu.selected = 0;
+            }
+            Entry::QuitToTitle => transitions.send(Transition::Replace(GameState::Title)),
+            Entry::QuitGame => quit.send(AppExit),
+        },
+        Page::Options => match config.bindings().get(menu.selected) {
+            Some(binding) => rebind.0 = Some(binding.action),
+            None => Setting::ALL[menu.selected - config.bindings().len()].change(&mut config),
+        },
+    }
+}
+
+fn render(
+    mut commands: Commands,
+    menu: Res<PauseMenu>,
+    config: Res<Config>,
+    rebind: Res<Rebind>,
+    locale: Res<Locale>,
+    root: Query<(Entity, Option<&Children>, ChangeTrackers<Menu>)>,
+) {
+    let (root, children, tracker) = match root.get_single() {
+        Ok(root) => root,
+        Err(_) => return,
+    };
+    if !(tracker.is_added()
+        || menu.is_changed()
+        || config.is_changed()
+        || rebind.is_changed()
+        || locale.is_changed())
+    {
+        return;
+    }
+
+    if let Some(children) = children {
+        for &child in children.iter() {
+            commands.entity(child).despawn_recursive();
+        }
+    }
+
+    let labels: Vec<String> = match menu.page {
+        Page::Main => Entry::ALL
+            .iter()
+            .map(|e| locale.text(e.key()).to_string())
+            .collect(),
+        Page::Options => config
+            .bindings()
+            .iter()
+            .map(|binding| match rebind.0 {
+                Some(action) if action == binding.action => {
+                    locale.fill("pause.rebind", &[("action", &format!("{:?}", action))])
+                }
+                _ => describe(binding),
+            })
+            .chain(Setting::ALL.iter().map(|s| s.label(&config, &locale)))
+            .collect(),
+    };
+
+    let size = match menu.page {
+        Page::Main => 40.0,
+        Page::Options => 20.0,
+    };
+
+    commands.entity(root).with_children(|parent| {
+        for (i, label) in labels.into_iter().enumerate() {
+            let color = match i == menu.selected {
+                true => Color::WHITE,
+                false => Color::GRAY,
+            };
+
+            parent
+                .spawn_bundle(ButtonBundle {
+                    style: Style {
+                        margin: Rect::all(Val::Px(size / 4.0)),
+                        ..default()
+                    },
+                    color: UiColor(Color::NONE),
+                    ..default()
+                })
+                .insert(Row(i))
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle {
+                        text: Text::with_section(
+                            label,
+                            TextStyle {
+                                font: locale.font.clone(),
+                                font_size: size,
+                                color,
+                            },
+                            default(),
+                        ),
+                        ..default()
+                    });
+                });
+        }
+    });
+}
+
+fn describe(binding: &Binding) -> String {
+    let inputs: Vec<String> = binding
+        .keys
+        .iter()
+        .map(|k| format!("{:?}", k))
+        .chain(binding.mouse.iter().map(|m| format!("Mouse {:?}", m)))
+        .chain(binding.buttons.iter().map(|b| format!("Pad {:?}", b)))
+        .collect();
+
+    format!("{:?}: {}", binding.action, inputs.join(", "))
+}
@@ -4,6 +4,8 @@ use std::path::PathBuf;
 
 use bevy::prelude::*;
 
+use crate::input::{self, Action, Binding};
+
 // const CONFIG: &str = "~/.config/freddy";
 
 fn path() -> PathBuf {
@@ -11,6 +13,10 @@ fn path() -> PathBuf {
     PathBuf::from(p)
 }
 
+fn language() -> String {
+    "en".to_string()
+}
+
 pub struct ConfigPlugin;
 
 impl Plugin for ConfigPlugin {
@@ -21,33 +27,60 @@ impl Plugin for ConfigPlugin {
 
 #[derive(Component, serde::Serialize, serde::Deserialize)]
 pub struct Config {
+    /// Folder name of the selected mod pack. Kept ahead of the tables, TOML
+    /// can't have plain values after them.
+    #[serde(default, rename = "mod")]
+    mod_pack: Option<String>,
+    #[serde(default = "language")]
+    language: String,
+    /// Ids of the unlocked achievements.
+    #[serde(default)]
+    achievements: Vec<String>,
     freddy: Freddy,
+    #[serde(default)]
+    captions: CaptionSettings,
+    #[serde(default)]
+    accessibility: Accessibility,
+    #[serde(default)]
+    cheats: Cheats,
+    #[serde(default = "input::default_bindings")]
+    bindings: Vec<Binding>,
 }
 
 impl Config {
     pub fn load() -> Config {
         let mut file: File = OpenOptions::new()
             .create(true)
+            .truncate(false)
             .read(true)
             .write(true)
             .open(path())
             .unwrap();
         let mut buf: String = String::new();
         file.read_to_string(&mut buf).unwrap();
-        match toml::from_str(&buf) {
-            Ok(conf) => {
+        match toml::from_str::<Config>(&buf) {
+            Ok(mut conf) => {
                 println!("config loaded");
+                // saved before clicks were left to the buttons, a click
+                // confirmed whatever was highlighted wherever it landed
+                for binding in conf.bindings.iter_mut() {
+                    if binding.action == Action::Confirm {
+                        binding.mouse.retain(|b| *b != MouseButton::Left);
+                    }
+                }
                 conf
             }
             Err(e) => {
                 println!("creating new save: {}", e);
                 Self {
-                    freddy: Freddy {
-                        level: 1,
-                        beatgame: 0,
-                        beat6: 0,
-                        beat7: 0,
-                    },
+                    mod_pack: None,
+                    language: language(),
+                    achievements: Vec::new(),
+                    freddy: Freddy::new(1, false, false, false),
+                    captions: CaptionSettings::default(),
+                    accessibility: Accessibility::default(),
+                    cheats: Cheats::default(),
+                    bindings: input::default_bindings(),
                 }
             }
         }
@@ -56,6 +89,7 @@ impl Config {
     pub fn save(&self) {
         let mut file: File = OpenOptions::new()
             .create(true)
+            .truncate(false)
             .read(true)
             .write(true)
             .open(path())
@@ -64,7 +98,9 @@ impl Config {
         let data = toml::to_vec(self).unwrap();
 
         file.seek(SeekFrom::Start(0)).unwrap();
-        file.write(&data).unwrap();
+        file.write_all(&data).unwrap();
+        // rebinding can make the file shorter, don't leave the old tail
+        file.set_len(data.len() as u64).unwrap();
         file.sync_all().unwrap();
     }
 
@@ -103,6 +139,139 @@ impl Config {
         self.freddy.beat7 = u8::from_bool(beat7);
         self.save();
     }
+
+    pub fn mod_pack(&self) -> Option<&str> {
+        self.mod_pack.as_deref()
+    }
+
+    pub fn set_mod_pack(&mut self, name: Option<String>) {
+        self.mod_pack = name;
+        self.save();
+    }
+
+    /// Code of the selected language, one of `locale::LANGUAGES`.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    pub fn set_language(&mut self, language: String) {
+        self.language = language;
+        self.save();
+    }
+
+    pub fn unlocked(&self, id: &str) -> bool {
+        self.achievements.iter().any(|a| a == id)
+    }
+
+    /// Records the achievement, returning whether it is new.
+    pub fn unlock(&mut self, id: &str) -> bool {
+        if self.unlocked(id) {
+            return false;
+        }
+        self.achievements.push(id.to_string());
+        self.save();
+        true
+    }
+
+    pub fn captions(&self) -> CaptionSettings {
+        self.captions
+    }
+
+    pub fn set_captions(&mut self, captions: CaptionSettings) {
+        self.captions = captions;
+        self.save();
+    }
+
+    pub fn accessibility(&self) -> Accessibility {
+        self.accessibility
+    }
+
+    pub fn set_accessibility(&mut self, accessibility: Accessibility) {
+        self.accessibility = accessibility;
+        self.save();
+    }
+
+    pub fn cheats(&self) -> Cheats {
+        self.cheats
+    }
+
+    pub fn set_cheats(&mut self, cheats: Cheats) {
+        self.cheats = cheats;
+        self.save();
+    }
+
+    pub fn bindings(&self) -> &[Binding] {
+        &self.bindings
+    }
+
+    pub fn rebind(&mut self, action: Action, change: impl FnOnce(&mut Binding)) {
+        match self.bindings.iter_mut().find(|b| b.action == action) {
+            Some(binding) => change(binding),
+            None => {
+                let mut binding = Binding {
+                    action,
+                    keys: Vec::new(),
+                    mouse: Vec::new(),
+                    buttons: Vec::new(),
+                };
+                change(&mut binding);
+                self.bindings.push(binding);
+            }
+        }
+        self.save();
+    }
+}
+
+/// How subtitles and sound captions are drawn.
+#[derive(Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct CaptionSettings {
+    pub enabled: bool,
+    pub size: f32,
+    /// Opacity of the box behind them.
+    pub background: f32,
+}
+
+impl Default for CaptionSettings {
+    fn default() -> Self {
+        CaptionSettings {
+            enabled: false,
+            size: 24.0,
+            background: 0.6,
+        }
+    }
+}
+
+/// Options for photosensitive and colorblind players. The limits they apply
+/// are in the `[accessibility]` table of the tuning file.
+#[derive(Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Accessibility {
+    /// Narrows the alpha jitter and slows the flicker of every effect.
+    pub reduced_flashing: bool,
+    /// Jumpscares fade to black with a quieter scream.
+    pub soften_jumpscares: bool,
+    /// Indicator colors that don't rely on telling red from green.
+    pub colorblind: bool,
+}
+
+/// The extras menu's cheats. They stay in the save while on, and no
+/// achievement unlocks until they are all off.
+#[derive(Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct Cheats {
+    /// Hours go by four times as fast.
+    pub fast_nights: bool,
+    /// Power never drops.
+    pub infinite_power: bool,
+    /// The camera map marks where the animatronics are.
+    pub radar: bool,
+}
+
+impl Cheats {
+    pub fn any(&self) -> bool {
+        self.fast_nights || self.infinite_power || self.radar
+    }
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -116,7 +285,7 @@ pub struct Freddy {
 impl Freddy {
     pub fn new(level: u8, beatgame: bool, beat6: bool, beat7: bool) -> Freddy {
         Freddy {
-            level: level,
+            level,
             beatgame: u8::from_bool(beatgame),
             beat6: u8::from_bool(beat6),
             beat7: u8::from_bool(beat7),
@@ -0,0 +1,320 @@
+use bevy::prelude::*;
+
+use crate::{
+    fade::{Cut, Fade, FadeId, FadeScreen, Faded},
+    GameState,
+};
+
+/// Seconds to fade to black before leaving a screen, and back after.
+const FADE: f32 = 0.25;
+
+/// Seconds a fade gets before the transition goes on without its `Faded`,
+/// so a lost one doesn't stop every screen change after it.
+const STALLED: f32 = FADE * 2.0;
+
+/// Every change of `GameState` goes through here: screens send a
+/// `Transition` and this applies it, at most one per frame, after checking it
+/// against `EDGES`. Leaving a screen fades to black and the next one fades in;
+/// pushing and popping, like the pause menu, is instant.
+///
+/// Rejected transitions are logged and dropped, so a screen that asks twice
+/// doesn't panic.
+pub struct TransitionPlugin;
+
+impl Plugin for TransitionPlugin {
+    fn build(&self, app: &mut App) {
+        debug_assert!(
+            unreachable().is_empty(),
+            "no transition leads to {:?}",
+            unreachable()
+        );
+
+        app.add_event::<Transition>()
+            .init_resource::<Controller>()
+            .add_system(request)
+            .add_system(advance.after(request));
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Transition {
+    /// Leave the current screen for another.
+    To(GameState),
+    /// Open a screen over the current one, which keeps its entities.
+    Push(GameState),
+    /// Close the screen pushed last.
+    Pop,
+    /// Close every screen for another, like quitting from the pause menu.
+    Replace(GameState),
+    /// Leave the current screen and enter it again.
+    Restart,
+    /// Go anywhere, past the graph. For the debug overlay.
+    Force(GameState),
+}
+
+/// The flow between screens, from the original's frames. `Restart`, `Pop`
+/// and `Force` aren't in it.
+pub const EDGES: &[(GameState, GameState)] = &[
+    (GameState::Frame17, GameState::Title),
+    (GameState::Title, GameState::Ad),
+    (GameState::Title, GameState::WhatDay),
+    (GameState::Title, GameState::Customize),
+    (GameState::Title, GameState::CreepyStart),
+    (GameState::Title, GameState::Trophies),
+    (GameState::Title, GameState::Extras),
+    (GameState::Ad, GameState::WhatDay),
+    (GameState::Customize, GameState::WhatDay),
+    (GameState::Customize, GameState::Title),
+    (GameState::WhatDay, GameState::Wait),
+    (GameState::WhatDay, GameState::Frame1),
+    (GameState::Wait, GameState::Frame1),
+    (GameState::Frame1, GameState::Pause),
+    (GameState::Frame1, GameState::NextDay),
+    (GameState::Frame1, GameState::Freddy),
+    (GameState::Frame1, GameState::Died),
+    // until there is a game over screen
+    (GameState::Frame1, GameState::Title),
+    (GameState::Pause, GameState::Title),
+    (GameState::Freddy, GameState::Died),
+    (GameState::Died, GameState::GameOver),
+    (GameState::GameOver, GameState::Title),
+    (GameState::NextDay, GameState::WhatDay),
+    (GameState::NextDay, GameState::TheEnd),
+    (GameState::NextDay, GameState::TheEnd2),
+    (GameState::NextDay, GameState::TheEnd3),
+    (GameState::NextDay, GameState::EndOfDemo),
+    // until there are ending screens
+    (GameState::NextDay, GameState::Title),
+    (GameState::TheEnd, GameState::Title),
+    (GameState::TheEnd2, GameState::Title),
+    (GameState::TheEnd3, GameState::Title),
+    (GameState::EndOfDemo, GameState::Title),
+    (GameState::CreepyStart, GameState::CreepyEnd),
+    (GameState::CreepyEnd, GameState::Title),
+    (GameState::Trophies, GameState::Title),
+    (GameState::Extras, GameState::Title),
+];
+
+pub fn allowed(from: &GameState, to: &GameState) -> bool {
+    EDGES.iter().any(|(f, t)| f == from && t == to)
+}
+
+/// States no path from the warning leads to.
+pub fn unreachable() -> Vec<GameState> {
+    let mut reached = vec![GameState::Frame17];
+    let mut i = 0;
+    while i < reached.len() {
+        for (from, to) in EDGES {
+            if *from == reached[i] && !reached.contains(to) {
+                reached.push(to.clone());
+            }
+        }
+        i += 1;
+    }
+
+    GameState::ALL
+        .iter()
+        .filter(|state| !reached.contains(state))
+        .cloned()
+        .collect()
+}
+
+impl Transition {
+    /// Why `state` can't take this transition, if it can't.
+    fn check(&self, state: &State<GameState>) -> Result<(), String> {
+        let from = state.current();
+        match self {
+            Transition::To(to) | Transition::Push(to) | Transition::Replace(to)
+                if !allowed(from, to) =>
+            {
+                Err(format!("no transition from {:?} to {:?}", from, to))
+            }
+            Transition::Pop if state.inactives().is_empty() => {
+                Err(format!("{:?} wasn't pushed", from))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn faded(&self) -> bool {
+        !matches!(self, Transition::Push(_) | Transition::Pop)
+    }
+
+    fn apply(self, state: &mut State<GameState>) {
+        let result = match self.clone() {
+            Transition::To(to) | Transition::Force(to) => state.set(to),
+            Transition::Push(to) => state.push(to),
+            Transition::Pop => state.pop(),
+            Transition::Replace(to) => state.replace(to),
+            Transition::Restart => state.restart(),
+        };
+        if let Err(e) = result {
+            warn!("transition: {:?} failed: {}", self, e);
+        }
+    }
+}
+
+enum Phase {
+    Idle,
+    /// Pushed or popped this frame; the state only changes at the end of the
+    /// stage, anything else asked for would be applied on the old one.
+    Applied,
+    /// Fading to black, then applying the transition.
+    Out(Transition),
+    In,
+}
+
+struct Controller {
+    phase: Phase,
+    /// Runs out when the fade of the current phase is taking too long.
+    stalled: Timer,
+}
+
+impl Default for Controller {
+    fn default() -> Self {
+        Controller {
+            phase: Phase::Idle,
+            stalled: Timer::from_seconds(STALLED, false),
+        }
+    }
+}
+
+/// Takes the first valid transition asked for this frame.
+fn request(
+    mut controller: ResMut<Controller>,
+    mut state: ResMut<State<GameState>>,
+    mut transitions: EventReader<Transition>,
+    mut screen: EventWriter<FadeScreen>,
+) {
+    for transition in transitions.iter() {
+        if !matches!(controller.phase, Phase::Idle) {
+            warn!(
+                "transition: {:?} rejected, already changing screens",
+                transition
+            );
+            continue;
+        }
+        if let Err(e) = transition.check(&state) {
+            warn!("transition: {:?} rejected, {}", transition, e);
+            continue;
+        }
+
+        info!("transition: {:?} from {:?}", transition, state.current());
+        controller.phase = match transition.faded() {
+            true => {
+                screen.send(FadeScreen {
+                    fade: Fade::Out(Cut::Fade(FADE)),
+                    id: FadeId::TransitionOut,
+                });
+                controller.stalled.reset();
+                Phase::Out(transition.clone())
+            }
+            false => {
+                transition.clone().apply(&mut state);
+                Phase::Applied
+            }
+        };
+    }
+}
+
+/// Applies the transition once the screen is black, and fades back in.
+fn advance(
+    time: Res<Time>,
+    mut controller: ResMut<Controller>,
+    mut state: ResMut<State<GameState>>,
+    mut faded: EventReader<Faded>,
+    mut screen: EventWriter<FadeScreen>,
+) {
+    let ids: Vec<FadeId> = faded.iter().map(|f| f.id).collect();
+    let stalled = controller.stalled.tick(time.delta()).finished();
+    let done = |id| match ids.contains(&id) {
+        true => true,
+        false if stalled => {
+            warn!("transition: no {:?} in {}s, going on", id, STALLED);
+            true
+        }
+        false => false,
+    };
+
+    let phase = std::mem::replace(&mut controller.phase, Phase::Idle);
+    controller.phase = match phase {
+        Phase::Applied => Phase::Idle,
+        Phase::Out(transition) if done(FadeId::TransitionOut) => {
+            transition.apply(&mut state);
+            screen.send(FadeScreen {
+                fade: Fade::In(Cut::Fade(FADE)),
+                id: FadeId::TransitionIn,
+            });
+            controller.stalled.reset();
+            Phase::In
+        }
+        Phase::In if done(FadeId::TransitionIn) => Phase::Idle,
+        phase => phase,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::ecs::event::Events;
+
+    use super::*;
+
+    #[test]
+    fn the_original_flow_is_allowed() {
+        let flow = [
+            GameState::Frame17,
+            GameState::Title,
+            GameState::Ad,
+            GameState::WhatDay,
+            GameState::Frame1,
+            GameState::NextDay,
+            GameState::WhatDay,
+        ];
+        for pair in flow.windows(2) {
+            assert!(allowed(&pair[0], &pair[1]), "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn every_state_is_reachable() {
+        assert_eq!(unreachable(), vec![]);
+    }
+
+    #[test]
+    fn skipping_the_night_is_rejected() {
+        let state = State::new(GameState::Title);
+        assert!(!allowed(&GameState::Title, &GameState::NextDay));
+        assert!(Transition::To(GameState::NextDay).check(&state).is_err());
+        assert!(Transition::To(GameState::Ad).check(&state).is_ok());
+    }
+
+    #[test]
+    fn popping_needs_a_push() {
+        let state = State::new(GameState::Frame1);
+        assert!(Transition::Pop.check(&state).is_err());
+        assert!(Transition::Push(GameState::Pause).check(&state).is_ok());
+    }
+
+    #[test]
+    fn one_transition_a_frame() {
+        let mut app = App::new();
+        app.add_event::<Transition>()
+            .add_event::<FadeScreen>()
+            .insert_resource(State::new(GameState::Title))
+            .init_resource::<Controller>()
+            .add_system(request);
+
+        let mut transitions = app.world.resource_mut::<Events<Transition>>();
+        transitions.send(Transition::To(GameState::Ad));
+        transitions.send(Transition::To(GameState::Trophies));
+        app.update();
+
+        let controller = app.world.resource::<Controller>();
+        assert!(matches!(
+            controller.phase,
+            Phase::Out(Transition::To(GameState::Ad))
+        ));
+        let fades = app.world.resource::<Events<FadeScreen>>();
+        assert_eq!(fades.iter_current_update_events().count(), 1);
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::HashMap;
+
+use bevy::{
+    asset::{AssetLoader, LoadContext, LoadedAsset},
+    prelude::*,
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+
+use crate::{animation::Animation, save::Accessibility};
+
+pub struct TuningPlugin;
+
+impl Plugin for TuningPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Tuning>()
+            .init_asset_loader::<TuningLoader>()
+            .insert_resource(Tuning::default())
+            .add_startup_system(setup)
+            .add_system(apply);
+    }
+}
+
+/// Timings copied from the original frames, in seconds.
+///
+/// Loaded from `game.tuning.toml` and copied into the `Tuning` resource
+/// whenever the file changes; anything missing from the file keeps its
+/// default.
+#[derive(Deserialize, TypeUuid, Clone)]
+#[uuid = "16cd410b-07fc-4ace-8fba-9177b77a9af2"]
+#[serde(default)]
+pub struct Tuning {
+    pub warning: WarningTuning,
+    pub office: OfficeTuning,
+    pub feed: FeedTuning,
+    pub captions: CaptionTuning,
+    pub days: DayTuning,
+    pub accessibility: AccessibilityTuning,
+    #[serde(rename = "animation")]
+    pub animations: HashMap<String, Animation>,
+}
+
+impl Default for Tuning {
+    fn default() -> Self {
+        let animations = [
+            (
+                "static",
+                Animation {
+                    frame: 0.016_835_017,
+                    frames: 8,
+                    alpha_every: 0.09,
+                    alpha: [0.42, 0.8],
+                    ..default()
+                },
+            ),
+            (
+                "blip",
+                Animation {
+                    frame: 0.166_666_67,
+                    frames: 8,
+                    alpha_every: 0.08,
+                    alpha: [0.22, 0.61],
+                    visible_every: 0.3,
+                    visible_chance: 1.0 / 3.0,
+                    ..default()
+                },
+            ),
+            (
+                "freddy",
+                Animation {
+                    frame: 0.08,
+                    weights: vec![97, 1, 1, 1],
+                    alpha_every: 0.3,
+                    alpha: [0.02, 1.0],
+                    ..default()
+                },
+            ),
+        ];
+
+        Self {
+            warning: default(),
+            office: default(),
+            feed: default(),
+            captions: default(),
+            days: default(),
+            accessibility: default(),
+            animations: animations
+                .into_iter()
+                .map(|(name, animation)| (name.to_string(), animation))
+                .collect(),
+        }
+    }
+}
+
+impl Tuning {
+    /// The animation called `name`, calmed down for reduced flashing.
+    pub fn animation(&self, name: &str, accessibility: Accessibility) -> Animation {
+        let animation = match self.animations.get(name) {
+            Some(animation) => animation.clone(),
+            None => {
+                warn!("tuning: no animation '{}'", name);
+                Animation::default()
+            }
+        };
+
+        match accessibility.reduced_flashing {
+            true => animation.calmed(&self.accessibility),
+            false => animation,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct WarningTuning {
+    pub timer: f32,
+    pub fade: f32,
+}
+
+impl Default for WarningTuning {
+    fn default() -> Self {
+        Self {
+            timer: 2.0,
+            fade: 1.1,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct OfficeTuning {
+    /// Pixels per second with the cursor on the edge of the window.
+    pub pan: f32,
+    /// Middle part of the window, from -1 to 1, that doesn't scroll.
+    pub dead_zone: f32,
+    /// Strength of the perspective warp.
+    pub warp: f32,
+    /// Odds of a lit hall light being dark for a flicker.
+    pub flicker: f32,
+    /// Seconds between flicker rolls.
+    pub flicker_every: f32,
+    /// Seconds an animatronic in the office waits to attack, unless the
+    /// monitor comes down first.
+    pub attack: f32,
+}
+
+impl Default for OfficeTuning {
+    fn default() -> Self {
+        Self {
+            pan: 600.0,
+            dead_zone: 0.4,
+            warp: 0.2,
+            flicker: 0.1,
+            flicker_every: 0.05,
+            attack: 30.0,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct FeedTuning {
+    /// Pixels per second a camera feed sweeps at.
+    pub pan: f32,
+    /// Seconds a feed rests at each end of its sweep.
+    pub wait: f32,
+    /// Seconds the blip shows for when switching cameras.
+    pub blip: f32,
+}
+
+impl Default for FeedTuning {
+    fn default() -> Self {
+        Self {
+            pan: 100.0,
+            wait: 1.0,
+            blip: 0.2,
+        }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct CaptionTuning {
+    /// Seconds a sound caption stays up.
+    pub cue: f32,
+}
+
+impl Default for CaptionTuning {
+    fn default() -> Self {
+        Self { cue: 3.0 }
+    }
+}
+
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct DayTuning {
+    /// Seconds the night's number shows before it.
+    pub what_day: f32,
+    /// Seconds 6 AM shows after it.
+    pub next_day: f32,
+}
+
+impl Default for DayTuning {
+    fn default() -> Self {
+        Self {
+            what_day: 2.0,
+            next_day: 3.0,
+        }
+    }
+}
+
+/// Limits applied by the accessibility options.
+#[derive(Deserialize, Clone)]
+#[serde(default)]
+pub struct AccessibilityTuning {
+    /// Widest alpha range an effect may jitter over with reduced flashing.
+    pub alpha: f32,
+    /// Fewest seconds between alpha changes and visibility rolls.
+    pub every: f32,
+    /// Fewest seconds per animation frame.
+    pub frame: f32,
+    /// Seconds a softened jumpscare takes to fade to black.
+    pub scare_fade: f32,
+    /// Volume of the scream in a softened jumpscare.
+    pub scare_volume: f32,
+}
+
+impl Default for AccessibilityTuning {
+    fn default() -> Self {
+        Self {
+            alpha: 0.15,
+            every: 0.5,
+            frame: 0.1,
+            scare_fade: 1.5,
+            scare_volume: 0.3,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TuningLoader;
+
+impl AssetLoader for TuningLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let tuning: Tuning = toml::from_slice(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(tuning));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tuning.toml"]
+    }
+}
+
+pub struct TuningHandle(Handle<Tuning>);
+
+fn setup(mut commands: Commands, asr: Res<AssetServer>) {
+    commands.insert_resource(TuningHandle(load!(asr, Tuning)));
+}
+
+fn apply(
+    mut events: EventReader<AssetEvent<Tuning>>,
+    handle: Res<TuningHandle>,
+    assets: Res<Assets<Tuning>>,
+    mut tuning: ResMut<Tuning>,
+) {
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle: h } | AssetEvent::Modified { handle: h }
+                if *h == handle.0 =>
+            {
+                if let Some(loaded) = assets.get(h) {
+                    info!("tuning reloaded");
+                    *tuning = loaded.clone();
+                }
+            }
+            _ => {}
+        }
+    }
+}
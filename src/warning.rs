@@ -1,5 +1,13 @@
 use super::GameState;
-use crate::{assets::GameAssets, despawn_unload, title::ArrowLocation};
+use crate::{
+    assets::GameAssets,
+    despawn_unload,
+    fade::{self, Cut, Fade, FadeId, Faded},
+    input::Action,
+    locale::{Locale, Localized},
+    transition::Transition,
+    tuning::Tuning,
+};
 use bevy::prelude::*;
 
 pub struct WarningPlugin;
@@ -9,10 +17,9 @@ impl Plugin for WarningPlugin {
         app.add_system_set(SystemSet::on_enter(GameState::Frame17).with_system(setup))
             .add_system_set(
                 SystemSet::on_update(GameState::Frame17)
-                    .with_system(fade)
                     .with_system(countdown)
-                    .with_system(keyboard)
-                    .with_system(mouse),
+                    .with_system(confirm)
+                    .with_system(faded),
             )
             .add_system_set(
                 SystemSet::on_exit(GameState::Frame17)
@@ -21,9 +28,6 @@ impl Plugin for WarningPlugin {
     }
 }
 
-#[derive(Component)]
-pub struct WarningScreen;
-
 #[derive(Component)]
 pub struct OnWarningScreen;
 
@@ -33,12 +37,18 @@ pub struct OnWarningScreen;
 #[derive(Deref, DerefMut)]
 pub struct WarningTimer(Timer);
 
-fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    locale: Res<Locale>,
+    tuning: Res<Tuning>,
+) {
+    let warning = GameAssets::Warning.file();
     commands.spawn_bundle(UiCameraBundle::default());
     commands
         .spawn_bundle(ImageBundle {
             color: UiColor(Color::rgba(1.0, 1.0, 1.0, 1.0)),
-            image: UiImage(asset_server.load(GameAssets::Warning.path())),
+            image: UiImage(asset_server.load(locale.image(warning))),
             style: Style {
                 //margin: Rect {
                 //    top: Val::Px(249.0),
@@ -51,8 +61,8 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                 // position: Rect { right: Val::Px(389.0), top: Val::Px(249.0), ..default() },
                 position: Rect {
                     //right: Val::Percent(30.390625),
-                    top: Val::Percent(34.583333333),
-                    bottom: Val::Percent(48.194444444),
+                    top: Val::Percent(34.583_332),
+                    bottom: Val::Percent(48.194_443),
                     left: Val::Percent(33.28125),
                     ..default()
                 },
@@ -62,64 +72,52 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             },
             ..default()
         })
+        .insert(Localized(warning.to_string()))
         .insert(OnWarningScreen);
 
-    commands.insert_resource(WarningTimer(Timer::from_seconds(2.0, false)));
-    commands.insert_resource(FadeTimer(Timer::from_seconds(1.1, false)));
+    commands.insert_resource(WarningTimer(Timer::from_seconds(
+        tuning.warning.timer,
+        false,
+    )));
 }
 
-#[derive(Deref, DerefMut)]
-pub struct FadeTimer(Timer);
-
 fn countdown(
+    mut commands: Commands,
     time: Res<Time>,
+    tuning: Res<Tuning>,
     mut timer: ResMut<WarningTimer>,
-    mut fade_timer: ResMut<FadeTimer>,
-    mut game_state: ResMut<State<GameState>>,
+    screen: Query<Entity, With<OnWarningScreen>>,
 ) {
-    if timer.tick(time.delta()).finished() || timer.paused() {
-        if fade_timer.tick(time.delta()).just_finished() {
-            game_state.set(GameState::Title).unwrap();
-        }
-    }
-}
-
-fn fade(timer: ResMut<FadeTimer>, mut query: Query<&mut UiColor, With<OnWarningScreen>>) {
-    if timer.percent_left() == 100.0 {
-        return;
-    }
-
-    for mut color in query.iter_mut() {
-        color.0.set_a(1.0 * timer.percent_left());
+    if timer.tick(time.delta()).just_finished() {
+        let fade = Fade::Out(Cut::Fade(tuning.warning.fade));
+        fade::fade_all(&mut commands, screen.iter(), fade, FadeId::Warning);
     }
 }
 
-fn keyboard(
-    mut game_state: ResMut<State<GameState>>,
-    keys: Res<Input<KeyCode>>,
+/// Skips ahead to the fade, or past it once it started.
+fn confirm(
+    mut commands: Commands,
+    tuning: Res<Tuning>,
+    mut transitions: EventWriter<Transition>,
+    actions: Res<Input<Action>>,
+    mouse: Res<Input<MouseButton>>,
     mut timer: ResMut<WarningTimer>,
+    screen: Query<Entity, With<OnWarningScreen>>,
 ) {
-    if keys.just_pressed(KeyCode::Return) || keys.just_pressed(KeyCode::NumpadEnter) {
-        if timer.finished() {
-            game_state.set(GameState::Title).unwrap();
+    // the warning has no buttons, a click anywhere skips it
+    if actions.just_pressed(Action::Confirm) || mouse.just_pressed(MouseButton::Left) {
+        if timer.finished() || timer.paused() {
+            transitions.send(Transition::To(GameState::Title));
         } else {
             timer.pause();
-            // timer.set_duration(Duration::from_secs_f32(2.0));
+            let fade = Fade::Out(Cut::Fade(tuning.warning.fade));
+            fade::fade_all(&mut commands, screen.iter(), fade, FadeId::Warning);
         }
     }
 }
 
-fn mouse(
-    mut game_state: ResMut<State<GameState>>,
-    mouse: Res<Input<MouseButton>>,
-    mut timer: ResMut<WarningTimer>,
-) {
-    if mouse.just_pressed(MouseButton::Left) {
-        if timer.finished() {
-            game_state.set(GameState::Title).unwrap();
-        } else {
-            timer.pause();
-            // timer.set_duration(Duration::from_secs_f32(2.0));
-        }
+fn faded(mut faded: EventReader<Faded>, mut transitions: EventWriter<Transition>) {
+    if faded.iter().any(|f| f.id == FadeId::Warning) {
+        transitions.send(Transition::To(GameState::Title));
     }
 }
@@ -0,0 +1,233 @@
+use bevy::{
+    ecs::system::{lifetimeless::SRes, SystemParamItem},
+    prelude::*,
+    reflect::TypeUuid,
+    render::{
+        render_asset::{PrepareAssetError, RenderAsset, RenderAssets},
+        render_resource::{
+            std140::{AsStd140, Std140},
+            BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+            BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType, Buffer,
+            BufferBindingType, BufferInitDescriptor, BufferSize, BufferUsages, SamplerBindingType,
+            ShaderStages, TextureSampleType, TextureViewDimension,
+        },
+        renderer::RenderDevice,
+    },
+    sprite::{
+        Material2d, Material2dPipeline, Material2dPlugin, MaterialMesh2dBundle, Mesh2dHandle,
+    },
+};
+
+/// Wide images seen through the original's cylindrical lens: the office and
+/// the camera feeds. A `Pan` scrolls the view across the image.
+pub struct WarpPlugin;
+
+impl Plugin for WarpPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(Material2dPlugin::<WarpMaterial>::default())
+            .add_system(scroll);
+    }
+}
+
+/// Where the view is across the image, from 0 at its left edge to 1 at its
+/// right edge.
+#[derive(Component, Default)]
+pub struct Pan {
+    pub at: f32,
+    /// Pixels between the two ends, filled in once the image has loaded.
+    pub range: f32,
+}
+
+impl Pan {
+    pub fn new(at: f32) -> Pan {
+        Pan { at, range: 0.0 }
+    }
+
+    /// Moves the view by `pixels`, stopping at either end.
+    pub fn scroll(&mut self, pixels: f32) {
+        if self.range > 0.0 {
+            self.at = (self.at + pixels / self.range).clamp(0.0, 1.0);
+        }
+    }
+}
+
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "0f3a5ae2-5f5c-4bd3-9cb6-6f0d9a1c51c7"]
+pub struct WarpMaterial {
+    pub texture: Handle<Image>,
+    /// Left edge of the view, as a fraction of the image width.
+    pub scroll: f32,
+    /// Width of the view, as a fraction of the image width.
+    pub view: f32,
+    /// How much taller the edges of the view are drawn than the centre.
+    pub strength: f32,
+}
+
+impl WarpMaterial {
+    pub fn new(texture: Handle<Image>, strength: f32) -> WarpMaterial {
+        WarpMaterial {
+            texture,
+            scroll: 0.0,
+            view: 1.0,
+            strength,
+        }
+    }
+}
+
+#[derive(AsStd140)]
+struct WarpUniform {
+    scroll: f32,
+    view: f32,
+    strength: f32,
+}
+
+pub struct GpuWarpMaterial {
+    _buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl RenderAsset for WarpMaterial {
+    type ExtractedAsset = WarpMaterial;
+    type PreparedAsset = GpuWarpMaterial;
+    type Param = (
+        SRes<RenderDevice>,
+        SRes<Material2dPipeline<WarpMaterial>>,
+        SRes<RenderAssets<Image>>,
+    );
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        material: Self::ExtractedAsset,
+        (render_device, pipeline, images): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let image = match images.get(&material.texture) {
+            Some(image) => image,
+            None => return Err(PrepareAssetError::RetryNextUpdate(material)),
+        };
+
+        let uniform = WarpUniform {
+            scroll: material.scroll,
+            view: material.view,
+            strength: material.strength,
+        };
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("warp_material_uniform"),
+            contents: uniform.as_std140().as_bytes(),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("warp_material_bind_group"),
+            layout: &pipeline.material2d_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(&image.sampler),
+                },
+            ],
+        });
+
+        Ok(GpuWarpMaterial {
+            _buffer: buffer,
+            bind_group,
+        })
+    }
+}
+
+impl Material2d for WarpMaterial {
+    fn fragment_shader(asr: &AssetServer) -> Option<Handle<Shader>> {
+        Some(load!(asr, WarpShader))
+    }
+
+    fn bind_group(material: &GpuWarpMaterial) -> &BindGroup {
+        &material.bind_group
+    }
+
+    fn bind_group_layout(render_device: &RenderDevice) -> BindGroupLayout {
+        render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("warp_material_layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: BufferSize::new(WarpUniform::std140_size_static() as u64),
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        })
+    }
+}
+
+/// A screen sized quad showing `material`, for `Pan` to scroll.
+pub fn bundle(
+    meshes: &mut Assets<Mesh>,
+    material: Handle<WarpMaterial>,
+    z: f32,
+) -> MaterialMesh2dBundle<WarpMaterial> {
+    MaterialMesh2dBundle {
+        mesh: Mesh2dHandle(meshes.add(Mesh::from(shape::Quad::new(Vec2::new(1280.0, 720.0))))),
+        material,
+        transform: Transform::from_xyz(0.0, 0.0, z),
+        ..default()
+    }
+}
+
+fn scroll(
+    images: Res<Assets<Image>>,
+    mut materials: ResMut<Assets<WarpMaterial>>,
+    mut query: Query<(&mut Pan, &Handle<WarpMaterial>)>,
+) {
+    for (mut pan, handle) in query.iter_mut() {
+        let material = match materials.get(handle) {
+            Some(material) => material,
+            None => continue,
+        };
+        let width = match images.get(&material.texture) {
+            Some(image) => image.texture_descriptor.size.width as f32,
+            None => continue,
+        };
+
+        let range = (width - 1280.0).max(0.0);
+        if pan.range != range {
+            pan.range = range;
+        }
+
+        let (scroll, view) = (pan.at * range / width, (1280.0 / width).min(1.0));
+        // only touch the asset on a change, it is re-uploaded every time
+        if material.scroll != scroll || material.view != view {
+            let material = materials.get_mut(handle).unwrap();
+            material.scroll = scroll;
+            material.view = view;
+        }
+    }
+}